@@ -0,0 +1,91 @@
+import { describe, expect, test } from "bun:test";
+import { buildAuthorsQuery, getAuthorInfo, getAuthors, getAuthorsStream } from "./authors.ts";
+import { PersonCache } from "./cache.ts";
+import { WikidataClient } from "./client.ts";
+import { MockSource, type SparqlJsonBindings } from "./fixtures.ts";
+
+// A row of getAuthorInfo's query, for a property with an entity value
+function fieldRow(property: string, label: string): SparqlJsonBindings {
+  return {
+    propID: { type: "uri", value: `http://www.wikidata.org/prop/direct/${property}` },
+    propLabel: { type: "literal", value: label, "xml:lang": "en" },
+    value: { type: "uri", value: "http://www.wikidata.org/entity/Q5" },
+    valueLabel: { type: "literal", value: "human", "xml:lang": "en" },
+  };
+}
+
+describe("getAuthorInfo", () => {
+  // P1000 sorts before P106 as a string, so this checks they're sorted as numbers
+  const rows = [
+    fieldRow("P106", "occupation"),
+    fieldRow("P6", "head of government"),
+    fieldRow("P1000", "record held"),
+  ];
+  const labelIds = async (sortOrder: "ascending" | "descending" | "none") => {
+    const fields = await getAuthorInfo(new MockSource(() => rows), "Q42", { sortOrder });
+    return fields.map((field) => field.labelId);
+  };
+
+  test("sorts fields by property number", async () => {
+    expect(await labelIds("ascending")).toEqual(["P6", "P106", "P1000"]);
+    expect(await labelIds("descending")).toEqual(["P1000", "P106", "P6"]);
+  });
+
+  test("keeps the query's order when not sorting", async () => {
+    expect(await labelIds("none")).toEqual(["P106", "P6", "P1000"]);
+  });
+});
+
+describe("getAuthors", () => {
+  test("finds the canonical label for a lowercase name by default", async () => {
+    const client = new MockSource(() => [
+      {
+        id: { type: "uri", value: "http://www.wikidata.org/entity/Q42" },
+        name: { type: "literal", value: "Douglas Adams", "xml:lang": "en" },
+      },
+    ]);
+
+    const [author] = await getAuthors(client, "douglas adams");
+    expect(author.name).toBe("Douglas Adams");
+    expect(client.queries[0]).toContain('FILTER(LCASE(?name) = LCASE("""douglas adams"""))');
+  });
+
+  test("keeps only the first row for a person with several images", async () => {
+    const row = (image: string): SparqlJsonBindings => ({
+      id: { type: "uri", value: "http://www.wikidata.org/entity/Q42" },
+      name: { type: "literal", value: "Douglas Adams", "xml:lang": "en" },
+      image: { type: "uri", value: `http://commons.wikimedia.org/wiki/Special:FilePath/${image}` },
+    });
+    const client = new MockSource(() => [row("First.jpg"), row("Second.jpg")]);
+
+    const authors = await getAuthors(client, "Douglas Adams");
+    expect(authors).toHaveLength(1);
+    expect(authors[0].imageUrl?.href).toBe(
+      "https://commons.wikimedia.org/wiki/Special:FilePath/First.jpg",
+    );
+  });
+
+  test("checks every label when the endpoint has no search service", () => {
+    const local = new WikidataClient("http://localhost:8834/sparql");
+    const query = buildAuthorsQuery("douglas adams", {}, local);
+    expect(query).not.toContain("wikibase:mwapi");
+    expect(query).toContain('FILTER(LCASE(?name) = LCASE("""douglas adams"""))');
+    expect(buildAuthorsQuery("douglas adams")).toContain("wikibase:mwapi");
+  });
+});
+
+describe("getAuthorsStream", () => {
+  test("yields each person once, and remembers them like getAuthors", async () => {
+    const row: SparqlJsonBindings = {
+      id: { type: "uri", value: "http://www.wikidata.org/entity/Q42" },
+      name: { type: "literal", value: "Douglas Adams", "xml:lang": "en" },
+    };
+    const client = Object.assign(new MockSource(() => [row, row]), {
+      personCache: new PersonCache(),
+    });
+
+    const authors = await Array.fromAsync(getAuthorsStream(client, "Douglas Adams"));
+    expect(authors.map((author) => author.id)).toEqual(["Q42"]);
+    expect(client.personCache?.get("Q42", ["en"])?.name).toBe("Douglas Adams");
+  });
+});
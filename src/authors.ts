@@ -0,0 +1,1367 @@
+import type { Term } from "@rdfjs/types";
+import { type SparqlSource, WikidataClient } from "./client.ts";
+import {
+  type PartialDate,
+  dateClause,
+  formatYear,
+  getDate,
+  parseDate,
+  qualifierDateClause,
+  statementClause,
+} from "./dates.ts";
+import { PGCiteError } from "./errors.ts";
+import {
+  type PID,
+  type QID,
+  parsePID,
+  parseQID,
+  parseUrl,
+  pidFromUrl,
+  qidFromUrl,
+} from "./ids.ts";
+import { type Place, getPlace } from "./places.ts";
+import {
+  type Language,
+  type Row,
+  escapeSPARQL,
+  fromSparql,
+  getOptionalValues,
+  getTyped,
+  getValues,
+  parseLanguage,
+} from "./sparql.ts";
+import { sanitizeText } from "./text.ts";
+
+// Optional information about a Person, which Wikidata may not have
+export interface PersonDetails {
+  born?: PartialDate;
+  died?: PartialDate;
+  imageUrl?: URL;
+  sitelinks?: number;
+}
+
+export class Person {
+  readonly name: string; // Ex. Douglas Adams
+  readonly description?: string; // Ex. English author and humourist (1952–2001), if there is one
+  readonly id: QID; // Ex. Q42
+  readonly idUrl: URL; // Ex. https://www.wikidata.org/entity/Q42
+  readonly born?: PartialDate; // Date of birth, ex. { year: 1952, month: 3, day: 11, precision: "day" }
+  readonly died?: PartialDate; // Date of death, ex. { year: 2001, month: 5, day: 11, precision: "day" }
+  readonly imageUrl?: URL; // Ex. https://commons.wikimedia.org/wiki/Special:FilePath/Douglas%20adams%20portrait.jpg
+  readonly sitelinks?: number; // Number of Wikipedia and other wiki pages about them, ex. 120
+
+  constructor(
+    name: string,
+    description: string | undefined,
+    id: string,
+    details: PersonDetails = {},
+  ) {
+    this.name = name;
+    this.description = description;
+    this.idUrl = parseUrl(id);
+    this.id = qidFromUrl(this.idUrl);
+    this.born = details.born;
+    this.died = details.died;
+    this.imageUrl = details.imageUrl;
+    this.sitelinks = details.sitelinks;
+  }
+
+  // The same entity, even if fetched in another language or with other details
+  // For sets and Map keys, use id, since objects are compared by reference there
+  equals(other: Person): boolean {
+    return this.id === other.id;
+  }
+
+  // Ex. Douglas Adams (English author and humourist (1952–2001)) [Q42], or Douglas Adams [Q42]
+  toString() {
+    const description = this.description === undefined ? "" : ` (${this.description})`;
+    return `${this.name}${description} [${this.id}]`;
+  }
+
+  toJSON() {
+    return {
+      name: this.name,
+      description: this.description,
+      id: this.id,
+      idUrl: this.idUrl.href,
+      born: this.born,
+      died: this.died,
+      imageUrl: this.imageUrl?.href,
+      sitelinks: this.sitelinks,
+    };
+  }
+}
+
+// Add people to the client's person cache, if it has one
+function rememberPeople(client: SparqlSource, people: Person[], languages: Language[]) {
+  for (const person of people) client.personCache?.set(person, languages);
+}
+
+// Get a short label for each person that tells apart people with the same name
+// Ex. William Carpenter (English physician, 1813–1885), William Carpenter (poet, b. 1940)
+export function disambiguate(people: Person[]): string[] {
+  return people.map((person) => {
+    const lifespan =
+      person.born !== undefined && person.died !== undefined
+        ? `${formatYear(person.born)}–${formatYear(person.died)}`
+        : person.born !== undefined
+          ? `b. ${formatYear(person.born)}`
+          : person.died !== undefined
+            ? `d. ${formatYear(person.died)}`
+            : "";
+    const details = [person.description ?? "", lifespan].filter((detail) => detail !== "");
+
+    return details.length > 0 ? `${person.name} (${details.join(", ")})` : person.name;
+  });
+}
+
+// How a name is matched against author labels
+// exact: the whole label, as given
+// caseInsensitive: the whole label, ignoring case, out of the labels the search service suggests for it
+// contains: any label containing the name, out of the labels the search service suggests for it
+// search: whatever the search service (https://www.wikidata.org/w/api.php?action=help&modules=wbsearchentities) finds, best match first
+// The search service is Wikidata's, through the Wikidata Query Service (see hasSearchService)
+// Other endpoints check every label instead, which finds labels the service wouldn't suggest but is slower,
+// and search falls back to contains
+export type MatchMode = "exact" | "caseInsensitive" | "contains" | "search";
+
+// How authors are ordered
+// none: however the match mode finds them (search and suggestion order, or no particular order for exact)
+// sitelinks: most wiki pages first, as a rough measure of how notable they are
+export type AuthorSort = "none" | "sitelinks";
+
+export interface AuthorSearchOptions {
+  matchMode?: MatchMode; // Default caseInsensitive
+  sortBy?: AuthorSort; // Default none
+  lang?: Language; // Language of the name and description (default en)
+  fallbackLanguages?: Language[]; // Tried in order for the description if there isn't one in lang
+  // Maximum number of authors, added to the query as LIMIT (default unlimited)
+  // The query service also caps results and run time on its end, regardless of this
+  limit?: number;
+  offset?: number; // Number of authors to skip, added to the query as OFFSET (default 0)
+}
+
+// Trim a name and collapse runs of whitespace inside it, ex. "  Douglas   Adams " -> "Douglas Adams"
+function normalizeName(name: string): string {
+  return name.trim().replace(/\s+/g, " ");
+}
+
+// Get a list of authors with a name (e.g. "Douglas Adams")
+// Whitespace in the name is normalized, but diacritics must still match exactly unless matchMode is search
+// Authors with no description in any of the languages are still found, with an undefined description
+export async function getAuthors(
+  client: SparqlSource,
+  name: string,
+  options: AuthorSearchOptions = {},
+): Promise<Person[]> {
+  client.logger?.debug("Getting authors:", name, options);
+  return await fetchAuthors(client, [name], options);
+}
+
+// Get authors with a name like getAuthors, but yield each one as soon as it's read, ex. to show them as they come
+// Sources that can't stream (see SparqlSource.streamRequest) are read all at once, then yielded one by one
+// Only the first of any duplicates is yielded, and stopping early stops reading the response
+export async function* getAuthorsStream(
+  client: SparqlSource,
+  name: string,
+  options: AuthorSearchOptions = {},
+): AsyncGenerator<Person> {
+  client.logger?.debug("Streaming authors:", name, options);
+  const query = buildAuthorsQuery(name, options, client);
+  const rows = client.streamRequest?.(query) ?? (await client.makeRequest(query));
+  yield* authorsFromRows(client, rows, options);
+}
+
+// Get the authors with any of several exact names in one request, grouped by name
+// Names with no authors map to an empty list
+export async function getAuthorsMulti(
+  client: SparqlSource,
+  names: string[],
+  lang = parseLanguage("en"),
+): Promise<Map<string, Person[]>> {
+  client.logger?.debug("Getting authors:", names, lang);
+  const authors = new Map(names.map((name): [string, Person[]] => [name, []]));
+  if (names.length === 0) return authors;
+
+  // Exact matching means each author's name is the (normalized) name it was found by
+  for (const person of await fetchAuthors(client, names, { matchMode: "exact", lang })) {
+    for (const [name, people] of authors) {
+      if (normalizeName(name) === person.name) people.push(person);
+    }
+  }
+
+  return authors;
+}
+
+// Whether a source can use the search service (wikibase:mwapi), which is only on the Wikidata Query Service
+// Sources without an endpoint, like a FixtureClient, are assumed to answer for Wikidata
+function hasSearchService(source?: SparqlSource): boolean {
+  if (source?.endpoint === undefined) return true;
+  return parseUrl(source.endpoint).host === parseUrl(WikidataClient.defaultEndpoint).host;
+}
+
+// The SELECT clause of a query for personFromBindings
+const personProjection = `SELECT
+  ?id            # Ex. Q42
+  ?name          # Ex. Douglas Adams
+  ?description   # Ex. English author and humourist (1952–2001)
+  ?born          # Ex. 1952-03-11T00:00:00Z
+  ?bornPrecision # Ex. 11 (day)
+  ?died          # Ex. 2001-05-11T00:00:00Z
+  ?diedPrecision # Ex. 11 (day)
+  ?image         # Ex. http://commons.wikimedia.org/wiki/Special:FilePath/Douglas%20adams%20portrait.jpg
+  ?sitelinks     # Ex. 120`;
+
+// SPARQL for how many wiki pages are about ?id, apart from personDetails so it can be sorted by before paging
+const sitelinksClause = "OPTIONAL { ?id wikibase:sitelinks ?sitelinks. }";
+
+// SPARQL for the details of each ?id personFromBindings reads, other than their name and sitelinks
+// The description is in the first of the languages that has one, and unbound if none of them do
+function personDetails(languages: Language[]): string {
+  // One OPTIONAL per language, ex. OPTIONAL { ?id schema:description ?description0. FILTER(...) }
+  const descriptions = languages.map(
+    (lang, i) =>
+      `OPTIONAL { ?id schema:description ?description${i}. FILTER((LANG(?description${i})) = "${lang}") }`,
+  );
+  const descriptionVariables = languages.map((_, i) => `?description${i}`);
+
+  return `# Their single-sentence entity description
+  ${descriptions.join("\n  ")}
+  BIND(COALESCE(${descriptionVariables.join(", ")}) AS ?description)
+
+  # Their lifespan, with the precision of each date (https://www.wikidata.org/wiki/Help:Dates#Precision)
+  ${dateClause("?id", "P569", "?born")}
+  ${dateClause("?id", "P570", "?died")}
+
+  OPTIONAL { ?id wdt:P18 ?image. } # Their portrait on Wikimedia Commons`;
+}
+
+// Build the query getAuthors makes for a name, without the prefixes (see WikidataClient.addPrefixes)
+// The query depends on whether the source can use the search service, so give it the source it's for
+export function buildAuthorsQuery(
+  name: string,
+  options: AuthorSearchOptions = {},
+  source?: SparqlSource,
+): string {
+  return authorsQuery([name], options, false, hasSearchService(source));
+}
+
+// Build a query for authors matching any of the names, which must be only one unless matchMode is exact
+// Counting only selects the number of authors, without the details (or sorting and paging) to fetch them
+// Without the search service, names are checked against every label rather than the candidates it finds
+function authorsQuery(
+  names: string[],
+  {
+    matchMode = "caseInsensitive",
+    sortBy = "none",
+    lang = parseLanguage("en"),
+    fallbackLanguages = [],
+    limit,
+    offset = 0,
+  }: AuthorSearchOptions,
+  count = false,
+  searchService = true,
+): string {
+  if (limit !== undefined && (!Number.isInteger(limit) || limit < 0)) {
+    throw new PGCiteError("invalidArgument", "Limit must be a non-negative integer!");
+  }
+  if (!Number.isInteger(offset) || offset < 0) {
+    throw new PGCiteError("invalidArgument", "Offset must be a non-negative integer!");
+  }
+  const isPaged = limit !== undefined || offset > 0;
+
+  const descriptionLanguages = [lang, ...fallbackLanguages].map(parseLanguage);
+  const escapedNames = names.map((name) => `"""${escapeSPARQL(normalizeName(name))}"""`);
+  const [escapedName] = escapedNames;
+  if (matchMode !== "exact" && names.length > 1) {
+    throw new PGCiteError(
+      "invalidArgument",
+      `Only one name can be searched for at a time in ${matchMode} mode!`,
+    );
+  }
+
+  // Searches return candidate ?ids in ?ordinal order, which then have their labels checked
+  const search = `
+  SERVICE wikibase:mwapi {
+    bd:serviceParam wikibase:endpoint "www.wikidata.org";
+      wikibase:api "EntitySearch";
+      mwapi:search ${escapedName};
+      mwapi:language "${lang}".
+    ?id wikibase:apiOutputItem mwapi:item.
+    ?ordinal wikibase:apiOrdinal true.
+  }`;
+
+  const useSearch = matchMode !== "exact" && searchService;
+  const match =
+    matchMode === "exact"
+      ? `
+  VALUES ?name {
+    ${escapedNames.map((escaped) => `${escaped}@${lang}`).join("\n    ")}
+  }`
+      : useSearch
+        ? search
+        : "";
+
+  const containsFilter = `FILTER(CONTAINS(LCASE(?name), LCASE(${escapedName})))`;
+  const nameFilter = {
+    exact: "",
+    caseInsensitive: `FILTER(LCASE(?name) = LCASE(${escapedName}))`,
+    contains: containsFilter,
+    search: useSearch ? "" : containsFilter,
+  }[matchMode];
+
+  const people = `${match}
+
+  ?id wdt:P31 wd:Q5;  # The ID of an instance of human,
+    rdfs:label ?name. # ...whose entity label matches ?name
+
+  FILTER((LANG(?name)) = "${lang}") # Only names in the given language
+  ${nameFilter}`;
+
+  if (count) {
+    return `
+SELECT
+  (COUNT(DISTINCT ?id) AS ?count) # Ex. 3
+WHERE {
+  ${people}
+}`;
+  }
+
+  // People without a sitelink count are unbound, which sorts last when descending
+  const orderBy = [
+    ...(sortBy === "sitelinks" ? ["DESC(?sitelinks)"] : []),
+    ...(useSearch ? ["?ordinal"] : []),
+    ...(isPaged ? ["?id"] : []), // So pages don't overlap between requests
+  ];
+  const orderClause = orderBy.length > 0 ? `ORDER BY ${orderBy.join(" ")}` : "";
+
+  // People are sorted and paged in a subquery, before their details are joined on
+  // Someone with several dates or images comes back once for each combination of them,
+  // so LIMIT and OFFSET would otherwise count rows rather than people
+  return `
+${personProjection}
+WHERE {
+  {
+    SELECT DISTINCT ?id ?name ?ordinal ?sitelinks WHERE {
+      ${people}
+
+      ${sitelinksClause}
+    }
+    ${orderClause}
+    ${limit === undefined ? "" : `LIMIT ${limit}`}
+    ${offset > 0 ? `OFFSET ${offset}` : ""}
+  }
+
+  ${personDetails(descriptionLanguages)}
+}
+${orderClause}`;
+}
+
+// Build the query countAuthors makes for a name, without the prefixes (see WikidataClient.addPrefixes)
+export function buildCountAuthorsQuery(
+  name: string,
+  options: AuthorSearchOptions = {},
+  source?: SparqlSource,
+): string {
+  return authorsQuery([name], options, true, hasSearchService(source));
+}
+
+// Count the authors with a name, without fetching them, ex. to see how many "John Smith"s there are first
+// Matches the same authors as getAuthors, but limit, offset, and sortBy don't apply
+export async function countAuthors(
+  client: SparqlSource,
+  name: string,
+  options: AuthorSearchOptions = {},
+): Promise<number> {
+  const [bindings] = await client.makeRequest(buildCountAuthorsQuery(name, options, client));
+  return bindings === undefined ? 0 : getTyped(bindings, "count", fromSparql.integer);
+}
+
+// Get a person from a row of a query that selects ?id, ?name, ?description, ?born, ?died, ?image, and ?sitelinks
+// Descriptions are cleaned up with sanitizeText if sanitize is set
+function personFromBindings(bindings: Row, sanitize = false): Person {
+  const [name, id] = getValues(bindings, "name", "id");
+  const [rawDescription] = getOptionalValues(bindings, "description");
+  const description =
+    sanitize && rawDescription !== undefined ? sanitizeText(rawDescription) : rawDescription;
+  const born = getDate(bindings, "born");
+  const died = getDate(bindings, "died");
+
+  // Commons file paths come back as http:// URLs
+  const [image] = getOptionalValues(bindings, "image");
+  const imageUrl = image === undefined ? undefined : parseUrl(image);
+  if (imageUrl !== undefined) imageUrl.protocol = "https:";
+
+  const [sitelinks] = getOptionalValues(bindings, "sitelinks");
+  return new Person(name, description, id, {
+    born,
+    died,
+    imageUrl,
+    sitelinks: sitelinks === undefined ? undefined : Number(sitelinks),
+  });
+}
+
+// Get the people from the rows of a query like personFromBindings takes
+// Keyed by ID, to keep only the first of any duplicates
+// A person comes back once per combination of their dates and images, if they have several
+function peopleFromResults(results: Row[], sanitize = false): Person[] {
+  const people = new Map<QID, Person>();
+  for (const bindings of results) {
+    const person = personFromBindings(bindings, sanitize);
+    if (!people.has(person.id)) people.set(person.id, person);
+  }
+
+  return [...people.values()];
+}
+
+// Get the authors from the rows of an authors query as they're read, adding each to the person cache
+// Only the first of any duplicates is yielded, like peopleFromResults, but the rows can be a stream
+async function* authorsFromRows(
+  client: SparqlSource,
+  rows: Iterable<Row> | AsyncIterable<Row>,
+  { lang = parseLanguage("en"), fallbackLanguages = [] }: AuthorSearchOptions,
+): AsyncGenerator<Person> {
+  const seen = new Set<QID>();
+  for await (const bindings of rows) {
+    const person = personFromBindings(bindings, client.sanitize);
+    if (seen.has(person.id)) continue;
+    seen.add(person.id);
+    rememberPeople(client, [person], [lang, ...fallbackLanguages]);
+    yield person;
+  }
+}
+
+// Get a list of authors matching any of the names, which must be only one unless matchMode is exact
+async function fetchAuthors(
+  client: SparqlSource,
+  names: string[],
+  options: AuthorSearchOptions = {},
+): Promise<Person[]> {
+  const results = await client.makeRequest(
+    authorsQuery(names, options, false, hasSearchService(client)),
+  );
+  return await Array.fromAsync(authorsFromRows(client, results, options));
+}
+
+// Get a person by their exact ID (ex. Q42), with their name and description in a given language
+// Someone already in the client's person cache is returned from there, without a query
+// Undefined if there's no such person, or they have no name in the language
+export async function getPerson(
+  client: SparqlSource,
+  id: QID,
+  lang = parseLanguage("en"),
+): Promise<Person | undefined> {
+  const target = parseQID(id);
+  const cached = client.personCache?.get(target, [lang]);
+  if (cached !== undefined) {
+    client.logger?.debug("Got person from the cache:", target);
+    return cached;
+  }
+
+  const query = `
+${personProjection}
+WHERE {
+  VALUES ?id {
+    wd:${target}
+  }
+
+  ?id wdt:P31 wd:Q5;
+    rdfs:label ?name.
+  FILTER((LANG(?name)) = "${parseLanguage(lang)}")
+
+  ${personDetails([lang])}
+  ${sitelinksClause}
+}`;
+
+  const people = peopleFromResults(await client.makeRequest(query), client.sanitize);
+  rememberPeople(client, people, [lang]);
+  return people[0];
+}
+
+// Get the people who co-authored (P50) any work with a given author, using an exact ID (ex. Q42)
+// Each coauthor is only included once, however many works they share
+export async function getCoauthors(
+  client: SparqlSource,
+  author: QID,
+  lang = parseLanguage("en"),
+): Promise<Person[]> {
+  client.logger?.debug("Getting coauthors:", author);
+  const target = parseQID(author);
+
+  const query = `
+${personProjection}
+WHERE {
+  {
+    # Everyone else credited as an author on any of their works, once each
+    SELECT DISTINCT ?id WHERE {
+      ?work wdt:P50 wd:${target}, ?id.
+      FILTER(?id != wd:${target})
+    }
+  }
+
+  ?id wdt:P31 wd:Q5; # Only people, not organizations
+    rdfs:label ?name.
+  FILTER((LANG(?name)) = "${parseLanguage(lang)}")
+
+  ${personDetails([lang])}
+  ${sitelinksClause}
+}`;
+
+  const people = peopleFromResults(await client.makeRequest(query), client.sanitize);
+  rememberPeople(client, people, [lang]);
+  return people;
+}
+
+// The people around an author in the influence graph, from getInfluences
+export interface Influences {
+  influencedBy: Person[]; // People who influenced them (their P737 statements)
+  influenced: Person[]; // People they influenced (others' P737 statements about them)
+}
+
+// Get who influenced a given author and who they influenced, using an exact ID (ex. Q42)
+// Both directions are read from influenced by (P737), since Wikidata has no inverse property for it
+// People are lightweight, with only their name and description, and organizations are left out
+export async function getInfluences(
+  client: SparqlSource,
+  author: QID,
+  lang = parseLanguage("en"),
+): Promise<Influences> {
+  client.logger?.debug("Getting influences:", author);
+  const target = parseQID(author);
+
+  const query = `
+SELECT
+  ?direction   # Ex. influencedBy
+  ?id          # Ex. Q42
+  ?name        # Ex. Douglas Adams
+  ?description # Ex. English author and humourist (1952–2001)
+WHERE {
+  { wd:${target} wdt:P737 ?id. BIND("influencedBy" AS ?direction) }
+  UNION
+  { ?id wdt:P737 wd:${target}. BIND("influenced" AS ?direction) }
+
+  ?id wdt:P31 wd:Q5;
+    rdfs:label ?name.
+  FILTER((LANG(?name)) = "${parseLanguage(lang)}")
+
+  OPTIONAL { ?id schema:description ?description. FILTER((LANG(?description)) = "${lang}") }
+}`;
+
+  const results = await client.makeRequest(query);
+  const inDirection = (direction: string) => {
+    const rows = results.filter((bindings) => getValues(bindings, "direction")[0] === direction);
+    // Not added to the person cache, since they're missing everything but their names
+    return peopleFromResults(rows, client.sanitize);
+  };
+
+  return { influencedBy: inDirection("influencedBy"), influenced: inDirection("influenced") };
+}
+
+// A suggestion from searchAuthors
+export interface SearchResult {
+  id: QID; // Ex. Q42
+  label: string; // Ex. Douglas Adams
+  description: string; // Ex. English author and humourist (1952–2001), or "" if there isn't one
+}
+
+// Suggest entities for a name as it's typed, best match first, ex. for autocomplete
+// Uses the search API (https://www.wikidata.org/w/api.php?action=help&modules=wbsearchentities)
+// rather than SPARQL, so it's faster and tolerates typos and partial names
+// The API can't filter by class, so results may include entities other than people
+export async function searchAuthors(
+  client: WikidataClient,
+  prefix: string,
+  lang = parseLanguage("en"),
+  limit = 7,
+): Promise<SearchResult[]> {
+  if (!Number.isInteger(limit) || limit < 1 || limit > 50) {
+    throw new PGCiteError("invalidArgument", "Limit must be an integer from 1 to 50!");
+  }
+
+  const response = (await client.apiRequest({
+    action: "wbsearchentities",
+    search: normalizeName(prefix),
+    language: parseLanguage(lang),
+    uselang: lang,
+    type: "item",
+    limit: String(limit),
+  })) as { search?: { id: string; label?: string; description?: string }[] };
+
+  if (!Array.isArray(response.search)) {
+    throw new PGCiteError("malformed", "Search response has no results list!");
+  }
+  return response.search.map((result) => ({
+    id: parseQID(result.id),
+    label: result.label ?? result.id,
+    description: result.description ?? "",
+  }));
+}
+
+export interface AuthorPage {
+  authors: Person[];
+  hasMore: boolean; // Whether there are more authors after this page
+}
+
+// Get one page of authors with a name, of at most limit authors starting after offset
+export async function getAuthorsPage(
+  client: SparqlSource,
+  name: string,
+  options: AuthorSearchOptions & { limit: number },
+): Promise<AuthorPage> {
+  // Fetch one extra to find out whether there's another page
+  const authors = await getAuthors(client, name, { ...options, limit: options.limit + 1 });
+  return { authors: authors.slice(0, options.limit), hasMore: authors.length > options.limit };
+}
+
+// What kind of thing a field's value is
+// entity: a Wikidata entity, ex. Q5 (or P31, L7, etc.)
+// uri: any other URI, ex. a Commons file
+// literal: a plain value, ex. a string, date, or number
+export type ValueKind = "entity" | "uri" | "literal";
+
+// Get the kind of value a term is, going by its type rather than what it looks like
+// Entities are IRIs under entityBase, the client's wd: prefix, ex. http://www.wikidata.org/entity/
+function valueKindOf(term: Term, entityBase: string): ValueKind {
+  if (term.termType !== "NamedNode") return "literal";
+  return term.value.startsWith(entityBase) ? "entity" : "uri";
+}
+
+// A qualifier on a statement, ex. { labelId: "P580", label: "start time", value: "1974-01-01T00:00:00Z" }
+export interface Qualifier {
+  labelId: PID;
+  label: string;
+  value: string; // The value's label, or the value itself for literals
+}
+
+// How a statement is ranked among the others for its property (https://www.wikidata.org/wiki/Help:Ranking)
+// preferred: the current or most accurate value, when there are several
+// normal: the default
+// deprecated: known to be wrong, but kept (ex. a widely repeated mistake)
+export type StatementRank = "preferred" | "normal" | "deprecated";
+
+const statementRanks: Record<string, StatementRank> = {
+  "http://wikiba.se/ontology#PreferredRank": "preferred",
+  "http://wikiba.se/ontology#NormalRank": "normal",
+  "http://wikiba.se/ontology#DeprecatedRank": "deprecated",
+};
+
+// Optional information about a Field
+// qualifiers and rank are only known when it's read from a statement (see getAuthorInfo's statements option)
+export interface FieldDetails {
+  valueTerm?: Term;
+  qualifiers?: Qualifier[];
+  rank?: StatementRank;
+}
+
+export class Field {
+  readonly value: string; // Ex. novelist
+  readonly label: string; // Ex. occupation
+  readonly labelId: PID; // Ex. P106
+  readonly labelIdUrl: URL; // Ex. https://www.wikidata.org/prop/direct/P106
+  readonly valueKind: ValueKind; // Ex. entity
+  // The value as the query engine gave it, with its IRI, language, or datatype, ex. for toTurtle
+  readonly valueTerm?: Term;
+  // Only fetched with getAuthorInfo's statements option, so these are empty or undefined otherwise
+  readonly qualifiers: Qualifier[];
+  readonly rank?: StatementRank; // Ex. preferred
+
+  constructor(
+    labelId: string,
+    label: string,
+    value: string,
+    valueKind: ValueKind,
+    details: FieldDetails = {},
+  ) {
+    this.value = value;
+    this.label = label;
+    this.labelIdUrl = parseUrl(labelId);
+    this.labelId = pidFromUrl(this.labelIdUrl);
+    this.valueKind = valueKind;
+    this.valueTerm = details.valueTerm;
+    this.qualifiers = details.qualifiers ?? [];
+    this.rank = details.rank;
+  }
+
+  // The same property with the same value
+  equals(other: Field): boolean {
+    return this.labelId === other.labelId && this.value === other.value;
+  }
+
+  // Ex. occupation: novelist, or position held: chair (start time: 1974-01-01T00:00:00Z)
+  toString() {
+    const qualifiers = this.qualifiers.map(({ label, value }) => `${label}: ${value}`);
+    const details = qualifiers.length > 0 ? ` (${qualifiers.join(", ")})` : "";
+    return `${this.label}: ${this.value}${details}`;
+  }
+
+  toJSON() {
+    return {
+      label: this.label,
+      labelId: this.labelId,
+      value: this.value,
+      labelIdUrl: this.labelIdUrl.href,
+      valueKind: this.valueKind,
+      qualifiers: this.qualifiers,
+      rank: this.rank,
+    };
+  }
+}
+
+// Order of fields by property number, ex. P6 before P106 when ascending
+export type SortOrder = "ascending" | "descending" | "none";
+
+export interface AuthorInfoOptions {
+  onlyWikidataEntities?: boolean; // Only fields with Wikidata entries, not literals (default true)
+  languages?: Language[]; // Label languages in order of preference (default en)
+  autoLanguage?: boolean; // Try the service's [AUTO_LANGUAGE] before languages (default true)
+  sortOrder?: SortOrder; // Default ascending
+  include?: PID[]; // Only these properties; takes precedence over exclude
+  exclude?: PID[]; // Every property except these
+  // Read each statement (p: and ps:) instead of direct claims (wdt:), to get their qualifiers and rank
+  // Direct claims are only the best-ranked values, but this includes every rank, even deprecated
+  // (see preferredOnly and withoutDeprecated); default false
+  statements?: boolean;
+  // Check that the entity exists first, throwing a notFound PGCiteError if it doesn't (default false)
+  // Otherwise a nonexistent entity gives no fields, the same as one with nothing matching the options
+  checkExists?: boolean;
+}
+
+// Whether an entity exists, meaning it has any statements, labels, or other data at all
+// Much cheaper than a full query, ex. to tell a mistyped ID apart from an entity with no matching data
+export async function entityExists(client: SparqlSource, id: QID): Promise<boolean> {
+  return await client.ask(`ASK { wd:${parseQID(id)} ?p ?o. }`);
+}
+
+// Build the query getAuthorInfo makes for an ID, without the prefixes (see WikidataClient.addPrefixes)
+// Throws a PGCiteError if the ID isn't valid, since it may have come from user input
+export function buildAuthorInfoQuery(
+  id: QID,
+  {
+    languages = [parseLanguage("en")],
+    autoLanguage = true,
+    include,
+    exclude,
+    statements = false,
+  }: AuthorInfoOptions = {},
+): string {
+  // Ex. VALUES ?propID { wdt:P106 wdt:P800 } or FILTER(?propID NOT IN (wdt:P18))
+  const toPredicates = (ids: PID[]) => ids.map((id) => `wdt:${parsePID(id)}`);
+  const propertyFilter =
+    include !== undefined
+      ? `VALUES ?propID { ${toPredicates(include).join(" ")} }`
+      : exclude !== undefined && exclude.length > 0
+        ? `FILTER(?propID NOT IN (${toPredicates(exclude).join(", ")}))`
+        : "";
+
+  // Ex. [AUTO_LANGUAGE],fr,en
+  const labelLanguages = [
+    ...(autoLanguage ? ["[AUTO_LANGUAGE]"] : []),
+    ...languages.map(parseLanguage),
+  ].join(",");
+
+  // Statements come back once per qualifier, for getAuthorInfo to group back together
+  const qualifierVariables = `
+  ?statement           # Ex. wds:Q42-1d7d0ea9-412f-8b5b-ba8d-405ab9ecf026
+  ?qualifier           # Ex. P1545
+  ?qualifierLabel      # Ex. series ordinal
+  ?qualifierValueLabel # Ex. 1
+  ?rank                # Ex. wikibase:PreferredRank`;
+
+  const claims = statements
+    ? `?prop wikibase:directClaim ?propID;
+    wikibase:claim ?claim;
+    wikibase:statementProperty ?statementProperty.
+  ?target ?claim ?statement.
+  ?statement wikibase:rank ?rank;
+    ?statementProperty ?value.
+
+  OPTIONAL {
+    ?qualifier wikibase:qualifier ?qualifierProperty.
+    ?statement ?qualifierProperty ?qualifierValue.
+  }`
+    : `?target ?propID ?value.
+
+  ?prop wikibase:directClaim ?propID.`;
+
+  return `
+SELECT DISTINCT
+  ?propID     # Ex. P734
+  ?propLabel  # Ex. family name
+  ?value      # Ex. Q351735
+  ?valueLabel # Ex. Adams${statements ? qualifierVariables : ""}
+WHERE {
+  VALUES ?target {
+    wd:${parseQID(id)}
+  }
+  ${propertyFilter}
+
+  ${claims}
+
+  # Fetchs the label for every ?variable, the result of which is stored in ?variableLabel
+  SERVICE wikibase:label { bd:serviceParam wikibase:language "${labelLanguages}". }
+}`;
+}
+
+// Get a field from a row of getAuthorInfo's query
+function fieldFromBindings(
+  bindings: Row,
+  client: SparqlSource,
+  qualifiers: Qualifier[] = [],
+): Field {
+  const values: string[] = getValues(bindings, "propID", "propLabel", "valueLabel");
+  if (client.sanitize) values[2] = sanitizeText(values[2]);
+  const entityBase = (client.prefixes ?? WikidataClient.defaultPrefixes).wd;
+  const valueKind = getTyped(bindings, "value", (term) => valueKindOf(term, entityBase));
+  const [rank] = getOptionalValues(bindings, "rank");
+  return new Field(...(values as [string, string, string]), valueKind, {
+    valueTerm: bindings.get("value"),
+    qualifiers,
+    rank: rank === undefined ? undefined : statementRanks[rank],
+  });
+}
+
+// Get the fields from getAuthorInfo's query with the statements option, which has a row per qualifier
+// Keyed by statement, so each becomes one field with all of its qualifiers
+function fieldsFromStatements(results: Row[], client: SparqlSource): Field[] {
+  const statements = new Map<string, { bindings: Row; qualifiers: Qualifier[] }>();
+  for (const bindings of results) {
+    const [statement] = getValues(bindings, "statement");
+    const entry = statements.get(statement) ?? { bindings, qualifiers: [] };
+    statements.set(statement, entry);
+
+    const [qualifier, label, value] = getOptionalValues(
+      bindings,
+      "qualifier",
+      "qualifierLabel",
+      "qualifierValueLabel",
+    );
+    if (qualifier === undefined || label === undefined || value === undefined) continue;
+    const labelId = pidFromUrl(parseUrl(qualifier));
+    entry.qualifiers.push({ labelId, label, value: client.sanitize ? sanitizeText(value) : value });
+  }
+
+  return [...statements.values()].map(({ bindings, qualifiers }) =>
+    fieldFromBindings(bindings, client, qualifiers),
+  );
+}
+
+// Get information about a given author, using an exact ID (ex. Q42)
+// Throws a PGCiteError if the ID isn't valid, since it may have come from user input
+export async function getAuthorInfo(
+  client: SparqlSource,
+  id: QID,
+  options: AuthorInfoOptions = {},
+): Promise<Field[]> {
+  client.logger?.debug("Getting author info:", id);
+  const {
+    onlyWikidataEntities = true,
+    sortOrder = "ascending",
+    checkExists = false,
+    statements = false,
+  } = options;
+  const query = buildAuthorInfoQuery(id, options);
+
+  if (checkExists && !(await entityExists(client, id))) {
+    throw new PGCiteError("notFound", `Entity ${id} doesn't exist!`);
+  }
+
+  const results = await client.makeRequest(query);
+  const fields = statements
+    ? fieldsFromStatements(results, client)
+    : results.map((bindings) => fieldFromBindings(bindings, client));
+
+  // Filters results to only those with Wikidata entries
+  // Ex. Q84 but not douglasadams
+  const result = onlyWikidataEntities
+    ? fields.filter((field) => field.valueKind === "entity")
+    : fields;
+
+  // Sorted here rather than in the query, since property IDs aren't 0-padded (P1000 < P106 as strings)
+  // Array.prototype.sort is stable, so multiple values for one property keep their order
+  const propertyNumber = (field: Field) => Number(field.labelId.slice(1)); // Ex. P106 -> 106
+  if (sortOrder === "ascending") result.sort((a, b) => propertyNumber(a) - propertyNumber(b));
+  if (sortOrder === "descending") result.sort((a, b) => propertyNumber(b) - propertyNumber(a));
+
+  return result;
+}
+
+// Get information about several authors, with at most concurrency requests in flight at once
+// Results are paired with their ID, in the order they finish
+// Requests still wait their turn under the client's rate limit
+export async function getAuthorInfoBatch(
+  client: SparqlSource,
+  ids: QID[],
+  options: AuthorInfoOptions = {},
+  concurrency = 4,
+): Promise<[QID, Field[]][]> {
+  if (!Number.isInteger(concurrency) || concurrency < 1) {
+    throw new PGCiteError("invalidArgument", "Concurrency must be a positive integer!");
+  }
+
+  const results: [QID, Field[]][] = [];
+  let next = 0;
+
+  // Each worker takes the next ID until there are none left
+  const work = async () => {
+    while (next < ids.length) {
+      const id = ids[next++];
+      results.push([id, await getAuthorInfo(client, id, options)]);
+    }
+  };
+
+  await Promise.all(Array.from({ length: Math.min(concurrency, ids.length) }, work));
+  return results;
+}
+
+// Keep only the preferred values of each property, or its normal ones if none are preferred
+// Deprecated values are always left out, and fields without a rank (from direct claims) are all kept
+export function preferredOnly(fields: Field[]): Field[] {
+  const hasPreferred = new Set(
+    fields.filter((field) => field.rank === "preferred").map((field) => field.labelId),
+  );
+  return fields.filter(
+    (field) =>
+      field.rank === undefined ||
+      field.rank === "preferred" ||
+      (field.rank === "normal" && !hasPreferred.has(field.labelId)),
+  );
+}
+
+// Leave out deprecated values, keeping every other rank
+export function withoutDeprecated(fields: Field[]): Field[] {
+  return fields.filter((field) => field.rank !== "deprecated");
+}
+
+// All the values of one property, ex. occupation: novelist, screenwriter
+export interface GroupedField {
+  label: string; // Ex. occupation
+  labelId: PID; // Ex. P106
+  values: string[]; // Ex. ["novelist", "screenwriter"]
+}
+
+// Collapse fields with the same property into one, in order of each property's first appearance
+export function groupFields(fields: Field[]): GroupedField[] {
+  const groups = new Map<PID, GroupedField>();
+
+  for (const field of fields) {
+    const { label, labelId, value } = field;
+    const group: GroupedField = groups.get(labelId) ?? { label, labelId, values: [] };
+    group.values.push(value);
+    groups.set(labelId, group);
+  }
+
+  return [...groups.values()];
+}
+
+// Get the URL of the Wikipedia article about an entity in a given language, if there is one
+// Ex. Q42, en -> https://en.wikipedia.org/wiki/Douglas_Adams
+export async function getWikipediaUrl(
+  client: SparqlSource,
+  id: QID,
+  lang = parseLanguage("en"),
+): Promise<URL | undefined> {
+  const query = `
+SELECT
+  ?article # Ex. https://en.wikipedia.org/wiki/Douglas_Adams
+WHERE {
+  # The sitelink for the entity on the given Wikipedia
+  ?article schema:about wd:${parseQID(id)};
+    schema:isPartOf <https://${parseLanguage(lang)}.wikipedia.org/>.
+}
+LIMIT 1`;
+
+  const [bindings] = await client.makeRequest(query);
+  return bindings === undefined ? undefined : parseUrl(getValues(bindings, "article")[0]);
+}
+
+// Get the aliases ("also known as") of an entity in a given language, deduplicated and sorted
+// These are the labels other than its main one, which a Person has as its name
+// Ex. Q42, en -> ["Douglas Noel Adams", "Douglas Noël Adams"]
+export async function getAliases(
+  client: SparqlSource,
+  id: QID,
+  lang = parseLanguage("en"),
+): Promise<string[]> {
+  const query = `
+SELECT
+  ?alias # Ex. Douglas Noel Adams
+WHERE {
+  wd:${parseQID(id)} skos:altLabel ?alias.
+  FILTER((LANG(?alias)) = "${parseLanguage(lang)}")
+}`;
+
+  const results = await client.makeRequest(query);
+  const aliases = results.map((bindings) => getValues(bindings, "alias")[0]);
+  return [...new Set(aliases)].sort((a, b) => a.localeCompare(b, lang));
+}
+
+// Get the place of birth (P19) of a person, with its coordinates (P625)
+// Returns undefined if they have no place of birth or it has no coordinates
+export async function getBirthplace(
+  client: SparqlSource,
+  id: QID,
+  lang = parseLanguage("en"),
+): Promise<Place | undefined> {
+  const query = `
+SELECT
+  ?birthplace            # Ex. Q350
+  ?birthplaceLabel       # Ex. Cambridge
+  ?birthplaceCoordinates # Ex. Point(0.119 52.205)
+WHERE {
+  wd:${parseQID(id)} wdt:P19 ?birthplace.
+  ?birthplace wdt:P625 ?birthplaceCoordinates.
+
+  SERVICE wikibase:label { bd:serviceParam wikibase:language "${parseLanguage(lang)}". }
+}
+LIMIT 1`;
+
+  const [bindings] = await client.makeRequest(query);
+  return bindings === undefined ? undefined : getPlace(bindings, "birthplace");
+}
+
+// An entity that another links to through a property, ex. an occupation of a person
+export interface LinkedEntity {
+  id: QID; // Ex. Q6625963
+  label: string; // Ex. novelist
+}
+
+// Get the entities an entity links to through a property, with their labels in a given language
+// Ex. Q42, P106 -> [{ id: "Q6625963", label: "novelist" }, ...]
+// Entities with no label in the language are labeled with their ID, as the label service does
+export async function getLinkedEntities(
+  client: SparqlSource,
+  id: QID,
+  property: PID,
+  lang = parseLanguage("en"),
+): Promise<LinkedEntity[]> {
+  const query = `
+SELECT DISTINCT
+  ?entity      # Ex. Q6625963
+  ?entityLabel # Ex. novelist
+WHERE {
+  wd:${parseQID(id)} wdt:${parsePID(property)} ?entity.
+  FILTER(STRSTARTS(STR(?entity), STR(wd:))) # Only entities, not unknown values
+
+  SERVICE wikibase:label { bd:serviceParam wikibase:language "${parseLanguage(lang)}". }
+}`;
+
+  return (await client.makeRequest(query)).map((bindings) => {
+    const [entity, label] = getValues(bindings, "entity", "entityLabel");
+    return { id: qidFromUrl(parseUrl(entity)), label };
+  });
+}
+
+// Get the occupations (P106) of a person, ex. to check whether they're a novelist
+export async function getOccupations(
+  client: SparqlSource,
+  id: QID,
+  lang = parseLanguage("en"),
+): Promise<LinkedEntity[]> {
+  return await getLinkedEntities(client, id, "P106", lang);
+}
+
+// Get the employers (P108) of a person, ex. universities and publishers they worked for
+// Authors with no employers give an empty list
+export async function getEmployers(
+  client: SparqlSource,
+  id: QID,
+  lang = parseLanguage("en"),
+): Promise<LinkedEntity[]> {
+  return await getLinkedEntities(client, id, "P108", lang);
+}
+
+// The languages of a person, from getLanguages
+export interface Languages {
+  native: LinkedEntity[]; // Native language (P103), ex. [{ id: "Q1860", label: "English" }]
+  spoken: LinkedEntity[]; // Languages spoken, written or signed (P1412), which usually include native ones
+}
+
+// Get the native languages (P103) and languages spoken (P1412) of a person, using an exact ID (ex. Q42)
+// Either list is empty if Wikidata doesn't have it
+export async function getLanguages(
+  client: SparqlSource,
+  id: QID,
+  lang = parseLanguage("en"),
+): Promise<Languages> {
+  const [native, spoken] = await Promise.all([
+    getLinkedEntities(client, id, "P103", lang),
+    getLinkedEntities(client, id, "P1412", lang),
+  ]);
+  return { native, spoken };
+}
+
+// A position a person held, from getPositions
+export interface Position {
+  role: LinkedEntity; // Ex. { id: "Q18941264", label: "President of the Royal Society" }
+  start?: PartialDate; // From its start time (P580) qualifier
+  end?: PartialDate; // From its end time (P582) qualifier, undefined if still held or not known
+}
+
+// Keep only the first row for each ?statement (see statementClause)
+// A statement gets a row for each of a qualifier it has more than one of, ex. two degrees
+function firstPerStatement(results: Row[]): Row[] {
+  const seen = new Set<string>();
+  return results.filter((bindings) => {
+    const [statement] = getValues(bindings, "statement");
+    if (seen.has(statement)) return false;
+    seen.add(statement);
+    return true;
+  });
+}
+
+// Get the positions (P39) a person held, using an exact ID (ex. Q42), with when they held each one
+// Positions are in the order they started, with undated ones last; deprecated statements are left out
+export async function getPositions(
+  client: SparqlSource,
+  id: QID,
+  lang = parseLanguage("en"),
+): Promise<Position[]> {
+  const query = `
+SELECT
+  ?statement      # Ex. wds:Q42-1d7d0ea9-412f-8b5b-ba8d-405ab9ecf026
+  ?position       # Ex. Q18941264
+  ?positionLabel  # Ex. President of the Royal Society
+  ?start          # Ex. 1990-01-01T00:00:00Z
+  ?startPrecision # Ex. 9 (year)
+  ?end            # Ex. 1995-01-01T00:00:00Z
+  ?endPrecision   # Ex. 9 (year)
+WHERE {
+  ${statementClause(`wd:${parseQID(id)}`, "P39", "?position")}
+
+  ${qualifierDateClause("?statement", "P580", "?start")}
+  ${qualifierDateClause("?statement", "P582", "?end")}
+
+  SERVICE wikibase:label { bd:serviceParam wikibase:language "${parseLanguage(lang)}". }
+}`;
+
+  const positions = firstPerStatement(await client.makeRequest(query)).map((bindings) => {
+    const [position, label] = getValues(bindings, "position", "positionLabel");
+    return {
+      role: { id: qidFromUrl(parseUrl(position)), label },
+      start: getDate(bindings, "start"),
+      end: getDate(bindings, "end"),
+    };
+  });
+
+  const startOf = ({ start }: Position) => start?.year ?? Infinity;
+  return positions.sort((a, b) => startOf(a) - startOf(b));
+}
+
+// A place a person studied, from getEducation
+export interface Education {
+  id: QID; // Ex. Q691283
+  label: string; // Ex. St John's College
+  degree?: LinkedEntity; // Academic degree (P512) qualifier, ex. { id: "Q1765120", label: "Bachelor of Arts" }
+}
+
+// Get where a person was educated (P69), with the degree they got there if the statement has one
+// Each statement is only included once, with its first degree; authors with none give an empty list
+export async function getEducation(
+  client: SparqlSource,
+  id: QID,
+  lang = parseLanguage("en"),
+): Promise<Education[]> {
+  const query = `
+SELECT
+  ?statement        # Ex. wds:Q42-1d7d0ea9-412f-8b5b-ba8d-405ab9ecf026
+  ?institution      # Ex. Q691283
+  ?institutionLabel # Ex. St John's College
+  ?degree           # Ex. Q1765120
+  ?degreeLabel      # Ex. Bachelor of Arts
+WHERE {
+  ${statementClause(`wd:${parseQID(id)}`, "P69", "?institution")}
+
+  OPTIONAL {
+    ?statement pq:P512 ?degree.
+    FILTER(STRSTARTS(STR(?degree), STR(wd:)))
+  }
+
+  SERVICE wikibase:label { bd:serviceParam wikibase:language "${parseLanguage(lang)}". }
+}`;
+
+  return firstPerStatement(await client.makeRequest(query)).map((bindings) => {
+    const [institution, label] = getValues(bindings, "institution", "institutionLabel");
+    const [degree, degreeLabel] = getOptionalValues(bindings, "degree", "degreeLabel");
+    return {
+      id: qidFromUrl(parseUrl(institution)),
+      label,
+      ...(degree !== undefined && {
+        degree: { id: qidFromUrl(parseUrl(degree)), label: degreeLabel ?? degree },
+      }),
+    };
+  });
+}
+
+// Get the countries of citizenship (P27) of a person, ex. Q42 -> [{ id: "Q145", label: "United Kingdom" }]
+// People can have several, and each is only included once
+export async function getCitizenships(
+  client: SparqlSource,
+  id: QID,
+  lang = parseLanguage("en"),
+): Promise<LinkedEntity[]> {
+  return await getLinkedEntities(client, id, "P27", lang);
+}
+
+// A person's name split for citations, ex. { given: "Douglas", family: "Adams" }
+export interface NameParts {
+  given?: string;
+  family?: string;
+}
+
+// Guess the parts of a name by splitting it on the last space, ex. Douglas Noël Adams -> Douglas Noël, Adams
+// Names with no spaces have no parts, since there's no telling which one it is
+export function splitName(name: string): NameParts {
+  const lastSpace = name.lastIndexOf(" ");
+  if (lastSpace === -1) return {};
+  return { given: name.slice(0, lastSpace), family: name.slice(lastSpace + 1) };
+}
+
+// Get the given names (P735) and family names (P734) of a person, using an exact ID (ex. Q42)
+// Several of either are joined with spaces, in their series ordinal (P1545) order when they have one
+// Ex. Q42 -> { given: "Douglas", family: "Adams" }
+// Parts with no statements are guessed from their label with splitName instead
+export async function getNameParts(
+  client: SparqlSource,
+  id: QID,
+  lang = parseLanguage("en"),
+): Promise<NameParts> {
+  const query = `
+SELECT
+  ?part        # Ex. given
+  ?nameLabel   # Ex. Douglas
+  ?ordinal     # Ex. 1
+  ?targetLabel # Ex. Douglas Adams
+WHERE {
+  VALUES ?target {
+    wd:${parseQID(id)}
+  }
+
+  OPTIONAL {
+    { ?target p:P735 ?statement. ?statement ps:P735 ?name. BIND("given" AS ?part) }
+    UNION
+    { ?target p:P734 ?statement. ?statement ps:P734 ?name. BIND("family" AS ?part) }
+    ?statement a wikibase:BestRank.
+    OPTIONAL { ?statement pq:P1545 ?ordinal. }
+  }
+
+  SERVICE wikibase:label { bd:serviceParam wikibase:language "${parseLanguage(lang)}". }
+}`;
+
+  const results = await client.makeRequest(query);
+  // Names without an ordinal go after the rest
+  const names: Record<keyof NameParts, { name: string; ordinal: number }[]> = {
+    given: [],
+    family: [],
+  };
+  for (const bindings of results) {
+    const [part, name, ordinal] = getOptionalValues(bindings, "part", "nameLabel", "ordinal");
+    if ((part === "given" || part === "family") && name !== undefined) {
+      names[part].push({ name, ordinal: ordinal === undefined ? Infinity : Number(ordinal) });
+    }
+  }
+
+  const joined = (part: keyof NameParts) => {
+    const sorted = names[part].sort((a, b) => a.ordinal - b.ordinal);
+    return sorted.length === 0 ? undefined : sorted.map(({ name }) => name).join(" ");
+  };
+
+  const [label] = results.length === 0 ? [] : getOptionalValues(results[0], "targetLabel");
+  const guessed = label === undefined ? {} : splitName(label);
+  return {
+    given: joined("given") ?? guessed.given,
+    family: joined("family") ?? guessed.family,
+  };
+}
+
+// Get the sex or gender (P21) of a person, ex. Q42 -> { id: "Q6581097", label: "male" }
+// The ID tells values apart whatever their label, ex. for choosing pronouns
+// Undefined if they have no claim for it (or only an unknown value); the first is used if there are several
+export async function getGender(
+  client: SparqlSource,
+  id: QID,
+  lang = parseLanguage("en"),
+): Promise<LinkedEntity | undefined> {
+  const [gender] = await getLinkedEntities(client, id, "P21", lang);
+  return gender;
+}
+
+// An award a person received, from getAwards
+export interface Award {
+  id: QID; // Ex. Q1056251
+  label: string; // Ex. Hugo Award for Best Novel
+  year?: number; // When they received it, from its point in time (P585) qualifier, ex. 1966
+}
+
+// Get the awards (P166) a person received, using an exact ID (ex. Q42)
+// Reads each award statement for its point in time (P585) qualifier, which direct claims don't have
+// Awards are in the order they were received, with undated ones last; deprecated statements are left out
+export async function getAwards(
+  client: SparqlSource,
+  id: QID,
+  lang = parseLanguage("en"),
+): Promise<Award[]> {
+  const query = `
+SELECT
+  ?statement  # Ex. wds:Q42-1d7d0ea9-412f-8b5b-ba8d-405ab9ecf026
+  ?award      # Ex. Q1056251
+  ?awardLabel # Ex. Hugo Award for Best Novel
+  ?date       # Ex. 1966-01-01T00:00:00Z
+WHERE {
+  ${statementClause(`wd:${parseQID(id)}`, "P166", "?award")}
+
+  OPTIONAL { ?statement pq:P585 ?date. }
+
+  SERVICE wikibase:label { bd:serviceParam wikibase:language "${parseLanguage(lang)}". }
+}`;
+
+  const awards = firstPerStatement(await client.makeRequest(query)).map((bindings) => {
+    const [award, label] = getValues(bindings, "award", "awardLabel");
+    const [date] = getOptionalValues(bindings, "date");
+    const year = date === undefined ? undefined : parseDate(date, 9)?.year;
+    return { id: qidFromUrl(parseUrl(award)), label, year };
+  });
+
+  const yearOf = (award: Award) => award.year ?? Infinity;
+  return awards.sort((a, b) => yearOf(a) - yearOf(b));
+}
+
+// Authority control identifiers for a person, as used in library catalogues
+export interface AuthorityIds {
+  viaf?: string; // VIAF ID (P214), ex. 113230702
+  isni?: string; // ISNI (P213), ex. 0000 0001 2144 0811
+  orcid?: string; // ORCID iD (P496), ex. 0000-0002-1825-0097
+  lccn?: string; // Library of Congress authority ID (P244), ex. n80076765
+}
+
+const authorityIdProperties: Record<keyof AuthorityIds, PID> = {
+  viaf: "P214",
+  isni: "P213",
+  orcid: "P496",
+  lccn: "P244",
+};
+
+// Get the authority control identifiers of a person, using an exact ID (ex. Q42)
+// These are literal values, so getAuthorInfo only includes them when onlyWikidataEntities is false
+export async function getAuthorIdentifiers(client: SparqlSource, id: QID): Promise<AuthorityIds> {
+  const names = Object.keys(authorityIdProperties) as (keyof AuthorityIds)[];
+  const target = parseQID(id);
+
+  // Ex. OPTIONAL { wd:Q42 wdt:P214 ?viaf. }
+  const optionals = names.map(
+    (name) => `OPTIONAL { wd:${target} wdt:${authorityIdProperties[name]} ?${name}. }`,
+  );
+
+  const query = `
+SELECT
+  ${names.map((name) => `?${name}`).join(" ")}
+WHERE {
+  ${optionals.join("\n  ")}
+}
+LIMIT 1`;
+
+  const [bindings] = await client.makeRequest(query);
+  if (bindings === undefined) return {};
+
+  const values = getOptionalValues(bindings, ...names);
+  return Object.fromEntries(names.map((name, i) => [name, values[i]])) as AuthorityIds;
+}
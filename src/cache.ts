@@ -0,0 +1,54 @@
+import type { Person } from "./authors.ts";
+import { PGCiteError } from "./errors.ts";
+import type { QID } from "./ids.ts";
+import type { Language } from "./sparql.ts";
+
+// Set a key in a map, evicting the oldest keys once it has more than capacity of them
+// The key counts as the newest, even if it was already there
+export function setBounded<K, V>(map: Map<K, V>, key: K, value: V, capacity: number) {
+  map.delete(key);
+  map.set(key, value);
+  // Maps iterate in insertion order, so the first key is the oldest
+  while (map.size > capacity) {
+    map.delete(map.keys().next().value as K);
+  }
+}
+
+// Remembers people by ID, so looking up someone already seen doesn't need a query (see getPerson)
+// Unlike a client's query cache, this finds people whatever query found them, ex. searches for other names
+// People are kept per list of languages, since their name and description depend on them
+export class PersonCache {
+  readonly capacity: number; // The oldest person is evicted once this is exceeded
+  private people = new Map<string, Person>(); // Keyed by ID and languages, ex. Q42 en,fr
+
+  // Throws a PGCiteError if capacity isn't a positive integer
+  constructor(capacity = 1000) {
+    if (!Number.isInteger(capacity) || capacity < 1) {
+      throw new PGCiteError("invalidArgument", "Person cache capacity must be a positive integer!");
+    }
+    this.capacity = capacity;
+  }
+
+  get size(): number {
+    return this.people.size;
+  }
+
+  get(id: QID, languages: Language[]): Person | undefined {
+    return this.people.get(`${id} ${languages.join(",")}`);
+  }
+
+  set(person: Person, languages: Language[]) {
+    setBounded(this.people, `${person.id} ${languages.join(",")}`, person, this.capacity);
+  }
+
+  // Forget a person in every language, ex. after they've been edited on Wikidata
+  invalidate(id: QID) {
+    for (const key of this.people.keys()) {
+      if (key.startsWith(`${id} `)) this.people.delete(key);
+    }
+  }
+
+  clear() {
+    this.people.clear();
+  }
+}
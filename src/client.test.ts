@@ -0,0 +1,88 @@
+import { describe, expect, test } from "bun:test";
+import { buildAuthorsQuery, getAuthors } from "./authors.ts";
+import { WikidataClient } from "./client.ts";
+import { PGCiteError } from "./errors.ts";
+import { MockSource } from "./fixtures.ts";
+
+describe("makeRequest", () => {
+  // The engine parses queries before sending them, so this never reaches the endpoint
+  test("throws a query error for a broken query", async () => {
+    const request = new WikidataClient().makeRequest("SELECT ?id WHERE { ?id wdt:P31 }");
+    await expect(request).rejects.toMatchObject({ kind: "query" });
+  });
+
+  test("gives no results without an error when nothing matches", async () => {
+    expect(await getAuthors(new MockSource(() => []), "Nobody At All")).toEqual([]);
+  });
+});
+
+describe("federated queries", () => {
+  const federated = `
+SELECT ?book WHERE {
+  SERVICE <https://lib.example/sparql> { ?book ?p ?o. }
+}`;
+
+  // A client that answers a query from its cache, so nothing is sent, and keeps the queries it logs
+  const cachedClient = (query: string) => {
+    const logged: unknown[] = [];
+    const client = new WikidataClient().withCache().withLogger({
+      debug: (message, ...data) => logged.push(...data),
+    });
+    client.cache?.set(client.addPrefixes(query), []);
+    return { client, logged };
+  };
+
+  test("passes an allowed federated service block through intact", async () => {
+    const { client, logged } = cachedClient(federated);
+    client.withFederatedEndpoints("https://lib.example/sparql");
+    expect(await client.makeRequest(federated)).toEqual([]);
+    expect(logged[0]).toContain("SERVICE <https://lib.example/sparql> { ?book ?p ?o. }");
+  });
+
+  test("rejects endpoints that aren't allowed", async () => {
+    await expect(new WikidataClient().makeRequest(federated)).rejects.toMatchObject({
+      kind: "query",
+    });
+  });
+
+  test("expands prefixed service names before checking them", async () => {
+    const client = new WikidataClient().withPrefixes({ lib: "https://lib.example/sparql" });
+    const query = "SELECT ?book WHERE { SERVICE lib: { ?book ?p ?o. } }";
+    await expect(client.makeRequest(query)).rejects.toMatchObject({ kind: "query" });
+  });
+
+  test("rejects services given by a variable", async () => {
+    const client = new WikidataClient().withFederatedEndpoints("https://lib.example/sparql");
+    for (const service of ["?endpoint", "SILENT $endpoint"]) {
+      const query = `SELECT ?book WHERE {
+  BIND(<https://lib.example/sparql> AS ?endpoint)
+  SERVICE ${service} { ?book ?p ?o. }
+}`;
+      await expect(client.makeRequest(query)).rejects.toMatchObject({ kind: "query" });
+    }
+  });
+
+  test("ignores SERVICE in strings", async () => {
+    const name = "SERVICE <http://x.example/>";
+    const { client } = cachedClient(buildAuthorsQuery(name));
+    expect(await getAuthors(client, name)).toEqual([]);
+  });
+});
+
+describe("withApiEndpoint", () => {
+  test("is read from PGCITE_API_ENDPOINT", () => {
+    const local = "http://localhost:8181/w/api.php";
+    expect(WikidataClient.fromEnv({ PGCITE_API_ENDPOINT: local }).apiEndpoint).toBe(local);
+    expect(WikidataClient.fromEnv({}).apiEndpoint).toBe(WikidataClient.defaultApiEndpoint);
+  });
+
+  test("throws a url error for anything but http:// and https://", () => {
+    let kind: string | undefined;
+    try {
+      new WikidataClient().withApiEndpoint("ftp://x.example/api.php");
+    } catch (error) {
+      if (error instanceof PGCiteError) kind = error.kind;
+    }
+    expect(kind).toBe("url");
+  });
+});
@@ -0,0 +1,589 @@
+import { QueryEngine } from "@comunica/query-sparql";
+import type { Bindings } from "@rdfjs/types";
+import { PersonCache, setBounded } from "./cache.ts";
+import { PGCiteError, errorMessage } from "./errors.ts";
+import { type QID, parseUrl } from "./ids.ts";
+import type { Row } from "./sparql.ts";
+
+// Reject with a timeout PGCiteError if the request hasn't settled within the given time
+// The request is given a signal that's aborted then, so it stops too rather than carrying on unseen
+function rejectAfter<T>(
+  milliseconds: number,
+  request: (signal: AbortSignal) => Promise<T>,
+): Promise<T> {
+  const controller = new AbortController();
+  let timer: ReturnType<typeof setTimeout> | undefined;
+  const timedOut = new Promise<never>((_, reject) => {
+    timer = setTimeout(() => {
+      const error = new PGCiteError("timeout", `Request timed out after ${milliseconds}ms`);
+      controller.abort(error);
+      reject(error);
+    }, milliseconds);
+  });
+
+  return Promise.race([request(controller.signal), timedOut]).finally(() => clearTimeout(timer));
+}
+
+// Whether an HTTP status is worth retrying (rate limiting or a server-side error)
+function isTransientStatus(status: number): boolean {
+  return status === 429 || status >= 500;
+}
+
+// How long a response's Retry-After header says to wait, in milliseconds
+// The header is either a number of seconds (ex. 120) or a date (ex. Wed, 21 Oct 2015 07:28:00 GMT)
+// Returns undefined if there's no header, or it can't be parsed
+function retryAfter(response: Response): number | undefined {
+  const header = response.headers.get("Retry-After")?.trim();
+  if (!header) return undefined;
+  if (/^\d+$/.test(header)) return Number(header) * 1000;
+  const date = Date.parse(header);
+  return Number.isNaN(date) ? undefined : Math.max(date - Date.now(), 0);
+}
+
+// Get a PGCiteError for an error that's the endpoint's fault or ours, rather than the request failing
+// Parse errors come from the engine's SPARQL parser, and HTTP 400s are the endpoint rejecting the query
+// Either way, the message has the explanation, ex. Parse error on line 3: ... Expecting 'VAR', got '}'
+// A 429 is only left after every retry, so it means the endpoint is still limiting the rate
+function endpointError(error: unknown): PGCiteError | undefined {
+  const message = errorMessage(error);
+  if (/\(HTTP status 429\)/.test(message)) {
+    return new PGCiteError("rateLimited", `Rate limited: ${message}`, error);
+  }
+  if (!/^Parse error/.test(message) && !/\(HTTP status 400\)/.test(message)) return undefined;
+  return new PGCiteError("query", `Invalid query: ${message}`, error);
+}
+
+// Get a PGCiteError for a query that couldn't be made
+function queryFailed(error: unknown): PGCiteError {
+  return (
+    endpointError(error) ??
+    new PGCiteError("network", `Query failed: ${errorMessage(error)}`, error)
+  );
+}
+
+// Get a PGCiteError for a response that couldn't be read, which can still be the endpoint's error
+function readFailed(error: unknown): PGCiteError {
+  return (
+    endpointError(error) ??
+    new PGCiteError("malformed", `Couldn't read response: ${errorMessage(error)}`, error)
+  );
+}
+
+// Remove comments and string literals from a query, so text in them (ex. a name being searched for)
+// can't be mistaken for part of the query; IRIs are kept whole, since they can contain a #
+function withoutLiterals(query: string): string {
+  return query.replace(
+    /<[^<>"{}|^`\\\s]*>|"""(?:[^"\\]|\\[\s\S]|"(?!""))*"""|'''(?:[^'\\]|\\[\s\S]|'(?!''))*'''|"(?:[^"\\\n]|\\.)*"|'(?:[^'\\\n]|\\.)*'|#[^\n]*/g,
+    (token) => (token.startsWith("<") ? token : token.startsWith("#") ? "" : '""'),
+  );
+}
+
+// Receives diagnostics about requests, ex. console
+export interface Logger {
+  debug(message: string, ...data: unknown[]): void;
+}
+
+// Anything that can answer SPARQL queries, which every query function takes
+// Implemented by WikidataClient for a real endpoint, FixtureClient for saved responses, and MockSource
+export interface SparqlSource {
+  endpoint?: string; // Where queries go, ex. https://query.wikidata.org/sparql; undefined if nowhere
+  logger?: Logger;
+  // Whether query functions clean up markup in descriptions and values (see sanitizeText)
+  // Raw values are given unless this is set
+  sanitize?: boolean;
+  personCache?: PersonCache; // People already found, by ID; undefined if disabled
+  // Name -> IRI, ex. for what a wd: entity IRI looks like; WikidataClient.defaultPrefixes if undefined
+  prefixes?: Readonly<Record<string, string>>;
+  makeRequest(query: string): Promise<Row[]>;
+  // Like makeRequest, but yielding each row as it arrives; sources without it are read all at once
+  streamRequest?(query: string): AsyncIterable<Row>;
+  ask(query: string): Promise<boolean>; // For ASK queries, which have an answer rather than rows
+}
+
+// Client for a Wikidata (or other Wikibase) SPARQL endpoint
+export class WikidataClient implements SparqlSource {
+  static readonly defaultEndpoint = "https://query.wikidata.org/sparql";
+  static readonly defaultApiEndpoint = "https://www.wikidata.org/w/api.php";
+  // https://meta.wikimedia.org/wiki/User-Agent_policy
+  static readonly defaultUserAgent = "PGCite/0.1 (https://github.com/DanielMcCord/PGCite)";
+  // Declared before every query, so queries can use them without declaring them themselves
+  static readonly defaultPrefixes: Readonly<Record<string, string>> = {
+    wikibase: "http://wikiba.se/ontology#",
+    wd: "http://www.wikidata.org/entity/",
+    wdt: "http://www.wikidata.org/prop/direct/",
+    p: "http://www.wikidata.org/prop/",
+    ps: "http://www.wikidata.org/prop/statement/",
+    psv: "http://www.wikidata.org/prop/statement/value/",
+    pq: "http://www.wikidata.org/prop/qualifier/",
+    pqv: "http://www.wikidata.org/prop/qualifier/value/",
+    bd: "http://www.bigdata.com/rdf#",
+    mwapi: "https://www.mediawiki.org/ontology#API/",
+    rdfs: "http://www.w3.org/2000/01/rdf-schema#",
+    schema: "http://schema.org/",
+    skos: "http://www.w3.org/2004/02/skos/core#",
+  };
+
+  endpoint: string; // Ex. https://query.wikidata.org/sparql
+  // For requests that aren't SPARQL queries, ex. searching (https://www.mediawiki.org/wiki/API:Main_page)
+  apiEndpoint: string = WikidataClient.defaultApiEndpoint;
+  userAgent: string = WikidataClient.defaultUserAgent;
+  attempts = 3; // Times a request is tried before a transient failure is given up on
+  backoff = 500; // Milliseconds to wait before the first retry, doubled after each one
+  timeout = 30_000; // Milliseconds a request (including retries) may take before it's abandoned
+  cache?: Map<string, Bindings[]>; // Keyed by full query, with prefixes; undefined if disabled
+  maxCacheEntries = 100; // The oldest entry is evicted once this is exceeded
+  personCache?: PersonCache; // People already found, by ID; undefined if disabled
+  // Minimum milliseconds between the starts of consecutive requests (default one per second)
+  // undefined disables rate limiting
+  minInterval: number | undefined = 1000;
+  logger?: Logger; // Nothing is logged unless this is set
+  sanitize = false;
+  prefixes: Record<string, string> = { ...WikidataClient.defaultPrefixes }; // Name -> IRI
+  // Ex. http://proxy.example.com:8080
+  // undefined leaves it to Bun's fetch, which uses HTTP_PROXY or HTTPS_PROXY (and NO_PROXY) if they're set
+  proxy?: string;
+  // Endpoints queries may federate with, using SERVICE <endpoint> { ... }, as normalized URLs
+  // Services built into the query service, in the wikibase: and bd: namespaces (ex. wikibase:label), are always allowed
+  federatedEndpoints = new Set<string>();
+
+  constructor(endpoint: string = WikidataClient.defaultEndpoint) {
+    this.endpoint = endpoint;
+  }
+
+  // Create a client configured by the PGCITE_ENDPOINT, PGCITE_API_ENDPOINT, and PGCITE_USER_AGENT
+  // environment variables
+  // Unset (or empty) variables fall back to the defaults, and builders called afterwards win over them
+  // Ex. WikidataClient.fromEnv().withUserAgent(...) ignores PGCITE_USER_AGENT
+  // Throws a PGCiteError if PGCITE_API_ENDPOINT isn't an http:// or https:// URL
+  static fromEnv(env: Record<string, string | undefined> = process.env): WikidataClient {
+    const client = new WikidataClient(env.PGCITE_ENDPOINT || WikidataClient.defaultEndpoint);
+    if (env.PGCITE_API_ENDPOINT) client.withApiEndpoint(env.PGCITE_API_ENDPOINT);
+    if (env.PGCITE_USER_AGENT) client.withUserAgent(env.PGCITE_USER_AGENT);
+    return client;
+  }
+
+  // Set the User-Agent sent with every request; an empty string is rejected
+  withUserAgent(userAgent: string): this {
+    if (userAgent.trim() === "") {
+      throw new PGCiteError("invalidArgument", "User-Agent must not be empty!");
+    }
+    this.userAgent = userAgent;
+    return this;
+  }
+
+  // Set how many times a request is tried (including the first time) before giving up
+  withRetries(attempts: number): this {
+    if (!Number.isInteger(attempts) || attempts < 1) {
+      throw new PGCiteError("invalidArgument", "Attempts must be a positive integer!");
+    }
+    this.attempts = attempts;
+    return this;
+  }
+
+  // Set the delay before the first retry in milliseconds
+  withBackoff(milliseconds: number): this {
+    if (milliseconds < 0) throw new PGCiteError("invalidArgument", "Backoff must not be negative!");
+    this.backoff = milliseconds;
+    return this;
+  }
+
+  // Set how long a request may take in milliseconds before it fails with a timeout PGCiteError
+  withTimeout(milliseconds: number): this {
+    if (milliseconds <= 0) throw new PGCiteError("invalidArgument", "Timeout must be positive!");
+    this.timeout = milliseconds;
+    return this;
+  }
+
+  // Enable or disable caching of responses by query, keeping at most maxEntries of them
+  withCache(enabled = true, maxEntries = this.maxCacheEntries): this {
+    if (!Number.isInteger(maxEntries) || maxEntries < 1) {
+      throw new PGCiteError("invalidArgument", "Max cache entries must be a positive integer!");
+    }
+    this.cache = enabled ? (this.cache ?? new Map()) : undefined;
+    this.maxCacheEntries = maxEntries;
+    return this;
+  }
+
+  clearCache() {
+    this.cache?.clear();
+  }
+
+  // Enable (with an empty cache) or disable remembering people by ID, keeping at most capacity of them
+  // See PersonCache and getPerson
+  // Throws a PGCiteError if capacity isn't a positive integer
+  withPersonCache(enabled = true, capacity = this.personCache?.capacity): this {
+    this.personCache = enabled ? new PersonCache(capacity) : undefined;
+    return this;
+  }
+
+  // Forget a person, so they're looked up again, ex. after they've been edited on Wikidata
+  // Queries that found them are still cached, so use clearCache too to refetch those
+  invalidate(id: QID) {
+    this.personCache?.invalidate(id);
+  }
+
+  // Set the minimum milliseconds between requests, or undefined to disable (e.g. for a local endpoint)
+  withRateLimit(milliseconds: number | undefined): this {
+    if (milliseconds !== undefined && milliseconds < 0) {
+      throw new PGCiteError("invalidArgument", "Rate limit interval must not be negative!");
+    }
+    this.minInterval = milliseconds;
+    return this;
+  }
+
+  private nextRequestAt = 0; // Timestamp (as from Date.now()) before which no request may start
+
+  // Wait until the rate limit allows another request
+  // The slot is reserved before waiting, so concurrent callers sharing this client queue up in order
+  private async waitForTurn() {
+    if (this.minInterval === undefined) return;
+
+    const now = Date.now();
+    const startAt = Math.max(now, this.nextRequestAt);
+    this.nextRequestAt = startAt + this.minInterval;
+
+    if (startAt > now) await Bun.sleep(startAt - now);
+  }
+
+  // The engine is created on first use and then shared by every request, along with its caches
+  private engine?: QueryEngine;
+
+  private getEngine(): QueryEngine {
+    if (this.engine === undefined) {
+      try {
+        this.engine = new QueryEngine();
+      } catch (error) {
+        throw new PGCiteError(
+          "engine",
+          `Couldn't create query engine: ${errorMessage(error)}`,
+          error,
+        );
+      }
+    }
+    return this.engine;
+  }
+
+  // Add prefixes to declare before every query, replacing any existing ones with the same names
+  // Ex. withPrefixes({ wdno: "http://www.wikidata.org/prop/novalue/" })
+  withPrefixes(prefixes: Record<string, string>): this {
+    for (const [name, iri] of Object.entries(prefixes)) {
+      if (!/^[a-z][\w.-]*$/i.test(name)) {
+        throw new PGCiteError("invalidArgument", `'${name}' is not a valid prefix name!`);
+      }
+      if (/[\s<>"{}|^`\\]/.test(iri)) {
+        throw new PGCiteError("invalidArgument", `'${iri}' is not a valid prefix IRI!`);
+      }
+    }
+    this.prefixes = { ...this.prefixes, ...prefixes };
+    return this;
+  }
+
+  // Route every request through an HTTP or HTTPS proxy, ex. http://proxy.example.com:8080
+  // Throws a PGCiteError if the URL is malformed or isn't for an HTTP(S) proxy
+  withProxy(url: string): this {
+    const proxy = parseUrl(url);
+    if (proxy.protocol !== "http:" && proxy.protocol !== "https:") {
+      throw new PGCiteError("url", `Proxy '${url}' must be an http:// or https:// URL!`);
+    }
+    this.proxy = proxy.href;
+    return this;
+  }
+
+  // Send requests that aren't SPARQL queries (see apiRequest) to another Wikibase's Action API,
+  // ex. http://localhost:8181/w/api.php for one running locally
+  // Throws a PGCiteError if the URL is malformed or isn't http:// or https://
+  withApiEndpoint(url: string): this {
+    const apiEndpoint = parseUrl(url);
+    if (apiEndpoint.protocol !== "http:" && apiEndpoint.protocol !== "https:") {
+      throw new PGCiteError("url", `API endpoint '${url}' must be an http:// or https:// URL!`);
+    }
+    this.apiEndpoint = apiEndpoint.href;
+    return this;
+  }
+
+  // Allow queries to federate with more SPARQL endpoints, ex. a library's own, with SERVICE <endpoint> { ... }
+  // The query service has its own list of endpoints it allows too, which this can't add to:
+  // https://www.mediawiki.org/wiki/Wikidata_Query_Service/User_Manual/SPARQL_Federation_endpoints
+  // Throws a PGCiteError if a URL is malformed or isn't http:// or https://
+  withFederatedEndpoints(...endpoints: string[]): this {
+    for (const endpoint of endpoints) {
+      const url = parseUrl(endpoint);
+      if (url.protocol !== "http:" && url.protocol !== "https:") {
+        throw new PGCiteError("url", `Endpoint '${endpoint}' must be an http:// or https:// URL!`);
+      }
+      this.federatedEndpoints.add(url.href);
+    }
+    return this;
+  }
+
+  // Check that every SERVICE in a query is built in, or one of the federated endpoints
+  // Prefixed service names (ex. lib:sparql) are expanded first, using the query's and the client's prefixes
+  // Comments and strings are ignored, so a SERVICE in a searched-for name isn't mistaken for one
+  // Throws a query PGCiteError naming the first endpoint that isn't allowed
+  private checkServices(query: string) {
+    const code = withoutLiterals(query);
+    const declarations = code.matchAll(/\bPREFIX\s+([\w.-]*):\s*<([^>]*)>/gi);
+    const prefixes: Record<string, string> = {
+      ...this.prefixes,
+      ...Object.fromEntries([...declarations].map(([, name, iri]) => [name, iri])),
+    };
+    const builtIns = [WikidataClient.defaultPrefixes.wikibase, WikidataClient.defaultPrefixes.bd];
+
+    const services = code.matchAll(
+      /\bSERVICE\s+(?:SILENT\s+)?(?:<([^>]*)>|((?:[a-z][\w.-]*)?):([\w.%-]*)|([?$]\w+))/gi,
+    );
+    for (const [, iri, prefix, local, variable] of services) {
+      // Its endpoint is only known once the query runs, so it could be any endpoint at all
+      if (variable !== undefined) {
+        throw new PGCiteError("query", `Service ${variable} isn't allowed, since it's a variable`);
+      }
+
+      const namespace = iri === undefined ? prefixes[prefix] : "";
+      if (namespace === undefined) {
+        throw new PGCiteError("query", `Service ${prefix}:${local} has an undeclared prefix`);
+      }
+
+      const service = iri ?? `${namespace}${local}`;
+      if (builtIns.some((builtIn) => service.startsWith(builtIn))) continue;
+      const endpoint = URL.canParse(service) ? new URL(service).href : service;
+      if (!this.federatedEndpoints.has(endpoint)) {
+        throw new PGCiteError(
+          "query",
+          `Federated endpoint <${service}> isn't allowed (see withFederatedEndpoints)`,
+        );
+      }
+    }
+  }
+
+  // Set where diagnostics go (ex. console), or undefined to stop logging
+  withLogger(logger: Logger | undefined): this {
+    this.logger = logger;
+    return this;
+  }
+
+  // Strip HTML and wiki markup and decode entities in descriptions and values (see sanitizeText)
+  // Off by default, so values are as Wikidata has them unless this is called
+  withSanitize(sanitize = true): this {
+    this.sanitize = sanitize;
+    return this;
+  }
+
+  // Used by the query engine in place of the global fetch, so every request carries our headers
+  // and goes through the proxy, if there is one (Bun's fetch supports a proxy option)
+  // Retries with exponential backoff on 429s, 5xxs, and connection errors, but not on other 4xxs
+  // When the response has a Retry-After header (ex. on a 429), it waits exactly that long instead
+  // Retries are requests too, so each one also waits its turn under the rate limit
+  private fetch = async (input: RequestInfo | URL, init?: RequestInit): Promise<Response> => {
+    const headers = new Headers(init?.headers);
+    headers.set("User-Agent", this.userAgent);
+
+    for (let attempt = 1; ; attempt++) {
+      const isLastAttempt = attempt >= this.attempts;
+
+      let delay = this.backoff * 2 ** (attempt - 1);
+      try {
+        const response = await fetch(input, {
+          ...init,
+          headers,
+          ...(this.proxy !== undefined && { proxy: this.proxy }),
+        });
+        if (isLastAttempt || !isTransientStatus(response.status)) return response;
+        delay = retryAfter(response) ?? delay;
+      } catch (error) {
+        // fetch only rejects when the connection fails or the request was aborted on purpose
+        if (isLastAttempt || init?.signal?.aborted) throw error;
+      }
+
+      await Bun.sleep(delay);
+      init?.signal?.throwIfAborted(); // Ex. the request timed out while waiting
+      await this.waitForTurn();
+    }
+  };
+
+  // The fetch for the query engine, with every request also aborted by a signal (ex. on a timeout)
+  private fetchWithSignal(signal: AbortSignal): typeof fetch {
+    return ((input: RequestInfo | URL, init?: RequestInit) =>
+      this.fetch(input, {
+        ...init,
+        signal: init?.signal ? AbortSignal.any([init.signal, signal]) : signal,
+      })) as typeof fetch;
+  }
+
+  // The query engine's context for a query, with its requests aborted by a signal
+  private queryContext(signal: AbortSignal) {
+    return { sources: [this.endpoint], fetch: this.fetchWithSignal(signal) };
+  }
+
+  // Get a query ready to send: declare its prefixes, check its SERVICE blocks, and log it
+  // Sending it waits for a turn first (see startQuery), which is separate so cached answers don't wait
+  private prepareQuery(query: string, description = "SPARQL query:"): string {
+    const queryWithPrefixes = this.addPrefixes(query);
+    this.checkServices(queryWithPrefixes);
+    this.logger?.debug(description, queryWithPrefixes);
+    return queryWithPrefixes;
+  }
+
+  // Get the engine once the rate limit allows another query
+  private async startQuery(): Promise<QueryEngine> {
+    const engine = this.getEngine();
+    await this.waitForTurn();
+    return engine;
+  }
+
+  // The query as it's actually sent, with the prefixes it may use declared before it
+  // Prefixes the query already declares are left out, since declaring one twice is an error
+  addPrefixes(query: string): string {
+    const declarations = [...query.matchAll(/^\s*PREFIX\s+([^\s:]*):/gim)];
+    const declared = new Set(declarations.map((match) => match[1]));
+    const missing = Object.entries(this.prefixes)
+      .filter(([name]) => !declared.has(name))
+      .map(([name, iri]) => `PREFIX ${name}: <${iri}>`);
+    return `\n${missing.join("\n")}\n${query}`;
+  }
+
+  // Make a request to the SPARQL endpoint, using a given SPARQL query (as it would be entered in https://query.wikidata.org/)
+  // Returns an array of bindings (https://comunica.dev/docs/query/getting_started/query_app/#3-3-consuming-binding-results-as-an-array)
+  // Throws a PGCiteError if the engine can't be created, the query is invalid, is rate limited, fails, or times out,
+  // or if the response can't be read
+  // No matches isn't an error, just an empty array
+  // Queries may use SERVICE blocks, as long as their endpoints are allowed (see withFederatedEndpoints)
+  // Identical queries are answered from the cache instead, if it's enabled
+  async makeRequest(query: string): Promise<Bindings[]> {
+    const queryWithPrefixes = this.prepareQuery(query);
+
+    const cached = this.cache?.get(queryWithPrefixes);
+    if (cached !== undefined) {
+      this.logger?.debug(`Got ${cached.length} bindings from the cache`);
+      return cached;
+    }
+
+    const engine = await this.startQuery();
+
+    const getBindings = async (signal: AbortSignal) => {
+      const bindingsStream = await engine
+        .queryBindings(queryWithPrefixes, this.queryContext(signal))
+        .catch((error) => {
+          throw queryFailed(error);
+        });
+
+      // The endpoint's response is only read here, so it can still turn out to be an error
+      return await bindingsStream.toArray().catch((error) => {
+        throw readFailed(error);
+      });
+    };
+
+    const start = performance.now();
+    const bindings = await rejectAfter(this.timeout, getBindings);
+    this.logger?.debug(
+      `Got ${bindings.length} bindings in ${Math.round(performance.now() - start)}ms`,
+    );
+
+    if (this.cache !== undefined) {
+      setBounded(this.cache, queryWithPrefixes, bindings, this.maxCacheEntries);
+    }
+
+    return bindings;
+  }
+
+  // Make a request like makeRequest, but yield each row as it's read instead of collecting them all first
+  // Nothing is cached, and the timeout only covers starting the query, since rows may be read slowly on purpose
+  // Stopping early (ex. breaking out of a for await loop) stops reading the response
+  async *streamRequest(query: string): AsyncGenerator<Bindings> {
+    const queryWithPrefixes = this.prepareQuery(query, "SPARQL query (streamed):");
+    const engine = await this.startQuery();
+
+    const getStream = (signal: AbortSignal) =>
+      engine.queryBindings(queryWithPrefixes, this.queryContext(signal)).catch((error) => {
+        throw queryFailed(error);
+      });
+    const bindingsStream = await rejectAfter(this.timeout, getStream);
+
+    let count = 0;
+    try {
+      for await (const bindings of bindingsStream) {
+        count++;
+        yield bindings;
+      }
+    } catch (error) {
+      throw readFailed(error);
+    } finally {
+      bindingsStream.destroy();
+      this.logger?.debug(`Streamed ${count} bindings`);
+    }
+  }
+
+  // Ask the SPARQL endpoint a yes-or-no question, using an ASK query, ex. ASK { wd:Q42 ?p ?o. }
+  // Throws a PGCiteError like makeRequest does, but answers aren't cached
+  async ask(query: string): Promise<boolean> {
+    const queryWithPrefixes = this.prepareQuery(query);
+    const engine = await this.startQuery();
+
+    const getAnswer = (signal: AbortSignal) =>
+      engine.queryBoolean(queryWithPrefixes, this.queryContext(signal)).catch((error) => {
+        throw queryFailed(error);
+      });
+
+    const start = performance.now();
+    const answer = await rejectAfter(this.timeout, getAnswer);
+    this.logger?.debug(`Got ${answer} in ${Math.round(performance.now() - start)}ms`);
+
+    return answer;
+  }
+
+  // Make a request to the MediaWiki Action API, ex. { action: "wbsearchentities", search: "Douglas" }
+  // Sends the same User-Agent as queries, with the same retries, rate limit, and timeout (but no cache)
+  // Returns the parsed JSON response
+  // Throws a PGCiteError if the request fails or times out, the API gives an error, or the response isn't JSON
+  async apiRequest(params: Record<string, string>): Promise<unknown> {
+    const url = new URL(this.apiEndpoint);
+    for (const [name, value] of Object.entries({ ...params, format: "json" })) {
+      url.searchParams.set(name, value);
+    }
+    this.logger?.debug("API request:", url.href);
+
+    await this.waitForTurn();
+
+    const getResponse = async (signal: AbortSignal) => {
+      const response = await this.fetch(url, { signal }).catch((error) => {
+        throw new PGCiteError("network", `API request failed: ${errorMessage(error)}`, error);
+      });
+      if (response.status === 429) {
+        throw new PGCiteError("rateLimited", "API request was rate limited (status 429)");
+      }
+      if (!response.ok) {
+        throw new PGCiteError("network", `API request failed with status ${response.status}`);
+      }
+
+      const json: { error?: { code: string; info?: string } } = await response
+        .json()
+        .catch((error) => {
+          throw new PGCiteError(
+            "malformed",
+            `Couldn't read response: ${errorMessage(error)}`,
+            error,
+          );
+        });
+      // Ex. { "error": { "code": "badvalue", "info": "Unrecognized value for parameter \"action\"" } }
+      if (json.error !== undefined) {
+        throw new PGCiteError("network", `API error: ${json.error.info ?? json.error.code}`);
+      }
+      return json;
+    };
+
+    const start = performance.now();
+    const json = await rejectAfter(this.timeout, getResponse);
+    this.logger?.debug(`Got a response in ${Math.round(performance.now() - start)}ms`);
+
+    return json;
+  }
+
+  // Run any SELECT query through this client, with the same retries, rate limit, and cache
+  // The query may use the client's prefixes without declaring them (see defaultPrefixes and withPrefixes)
+  // Returns one object per result, mapping each bound variable's name to its value
+  // Ex. SELECT ?item WHERE { ?item wdt:P31 wd:Q5. } LIMIT 1 -> [{ item: "http://www.wikidata.org/entity/Q23" }]
+  async queryRaw(query: string): Promise<Record<string, string>[]> {
+    return (await this.makeRequest(query)).map((bindings) =>
+      Object.fromEntries([...bindings].map(([variable, term]) => [variable.value, term.value])),
+    );
+  }
+}
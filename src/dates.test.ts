@@ -0,0 +1,47 @@
+import { describe, expect, test } from "bun:test";
+import { getPerson } from "./authors.ts";
+import { formatDate, formatYear, parseDate } from "./dates.ts";
+import { MockSource } from "./fixtures.ts";
+
+const xsd = "http://www.w3.org/2001/XMLSchema#";
+
+describe("parseDate", () => {
+  test("keeps only the parts a date is precise to", () => {
+    expect(parseDate("1952-01-01T00:00:00Z", 9)).toEqual({ year: 1952, precision: "year" });
+    expect(parseDate("1952-03-11T00:00:00Z", 11)).toEqual({
+      year: 1952,
+      month: 3,
+      day: 11,
+      precision: "day",
+    });
+  });
+
+  test("reads astronomical years as years BCE, with no year 0", () => {
+    expect(parseDate("-0799-01-01T00:00:00Z", 9)).toEqual({ year: -800, precision: "year" });
+    expect(parseDate("0000-01-01T00:00:00Z", 9)).toEqual({ year: -1, precision: "year" });
+    expect(parseDate("0001-01-01T00:00:00Z", 9)).toEqual({ year: 1, precision: "year" });
+  });
+});
+
+describe("formatDate", () => {
+  test("writes years BCE back as astronomical years", () => {
+    expect(formatDate({ year: -800, precision: "year" })).toBe("-0799");
+    expect(formatDate({ year: -1, precision: "year" })).toBe("0000");
+    expect(formatDate({ year: 1952, month: 3, precision: "month" })).toBe("1952-03");
+  });
+});
+
+test("a negative year binding is a person's date BCE", async () => {
+  const client = new MockSource(() => [
+    {
+      id: { type: "uri", value: "http://www.wikidata.org/entity/Q6691" },
+      name: { type: "literal", value: "Homer", "xml:lang": "en" },
+      born: { type: "literal", value: "-0799-01-01T00:00:00Z", datatype: `${xsd}dateTime` },
+      bornPrecision: { type: "literal", value: "9", datatype: `${xsd}integer` },
+    },
+  ]);
+
+  const person = await getPerson(client, "Q6691");
+  expect(person?.born).toEqual({ year: -800, precision: "year" });
+  expect(person?.born && formatYear(person.born)).toBe("800 BCE");
+});
@@ -0,0 +1,121 @@
+import type { PID } from "./ids.ts";
+import { type Row, fromSparql, getOptionalValues, getTyped } from "./sparql.ts";
+
+// How precisely a date is known, from Wikidata's precision numbers (6 through 11)
+// Anything coarser than a millennium (ex. a billion years) counts as a millennium
+export type DatePrecision = "millennium" | "century" | "decade" | "year" | "month" | "day";
+
+const precisions: DatePrecision[] = ["millennium", "century", "decade", "year", "month", "day"];
+
+// A date from Wikidata, with only the parts it's precise to
+// Ex. { year: 1952, precision: "year" } for a date only known to the year
+// Years BCE are negative, ex. -800 for 800 BCE (there's no year 0)
+// Coarser dates keep the year Wikidata gives, ex. { year: 1950, precision: "decade" } for the 1950s
+export interface PartialDate {
+  year: number;
+  month?: number;
+  day?: number;
+  precision: DatePrecision;
+}
+
+// Parse an xsd:dateTime value with its Wikidata precision (https://www.wikidata.org/wiki/Help:Dates#Precision)
+// Ex. 1952-03-11T00:00:00Z with precision 9 (year) -> { year: 1952, precision: "year" }
+// Values use astronomical years (as in XSD 1.1), where 0000 is 1 BCE and -0799 is 800 BCE,
+// so years BCE come out negative with no year 0, ex. -0799-01-01T00:00:00Z -> { year: -800, ... }
+// Returns undefined for values that can't be parsed
+export function parseDate(value: string, precision: number): PartialDate | undefined {
+  const match = value.match(/^([+-]?)(\d+)-(\d{2})-(\d{2})T/);
+  if (match === null) return undefined;
+
+  const [year, month, day] = match.slice(2).map(Number);
+  const astronomicalYear = match[1] === "-" ? -year : year;
+  return {
+    year: astronomicalYear <= 0 ? -(1 - astronomicalYear) : astronomicalYear,
+    ...(precision >= 10 && { month }),
+    ...(precision >= 11 && { day }),
+    precision: precisions[Math.min(Math.max(precision - 6, 0), precisions.length - 1)],
+  };
+}
+
+// Whether a date is only known to a decade or coarser, so its year is approximate
+export function isApproximate(date: PartialDate): boolean {
+  return precisions.indexOf(date.precision) < precisions.indexOf("year");
+}
+
+// Whether a date is BCE, ex. { year: -800 }
+export function isBce(date: PartialDate): boolean {
+  return date.year < 0;
+}
+
+// Format a date as ISO 8601, only as precisely as it's known, ex. 1952-03
+// Years BCE go back to astronomical years, ex. { year: -800 } -> -0799 and { year: -1 } -> 0000
+export function formatDate({ year, month, day }: PartialDate): string {
+  const astronomicalYear = year < 0 ? year + 1 : year;
+  const parts = [Math.abs(astronomicalYear), month, day]
+    .filter((part) => part !== undefined)
+    .map((part, i) => String(part).padStart(i === 0 ? 4 : 2, "0"))
+    .join("-");
+  return astronomicalYear < 0 ? `-${parts}` : parts;
+}
+
+// Format the year of a date for people to read, ex. 1952, 1950s, 19th century, or 800 BCE
+export function formatYear(date: PartialDate): string {
+  const year = Math.abs(date.year);
+  const era = isBce(date) ? " BCE" : "";
+  switch (date.precision) {
+    case "millennium":
+      return `${ordinal(Math.floor((year - 1) / 1000) + 1)} millennium${era}`;
+    case "century":
+      return `${ordinal(Math.floor((year - 1) / 100) + 1)} century${era}`;
+    case "decade":
+      return `${Math.floor(year / 10) * 10}s${era}`;
+    default:
+      return `${year}${era}`;
+  }
+}
+
+const ordinalSuffixes: Record<number, string> = { 1: "st", 2: "nd", 3: "rd" };
+
+// Ex. 1 -> 1st, 12 -> 12th, 22 -> 22nd
+function ordinal(n: number): string {
+  const isTeen = n % 100 >= 11 && n % 100 <= 13;
+  return `${n}${isTeen ? "th" : (ordinalSuffixes[n % 10] ?? "th")}`;
+}
+
+// SPARQL for getting a subject's best-ranked date for a property, along with its precision
+// Ex. ?id, P569, ?born binds ?born to 1952-03-11T00:00:00Z and ?bornPrecision to 11 (day)
+export function dateClause(subject: string, property: PID, variable: string): string {
+  return `OPTIONAL {
+    ${subject} p:${property} [a wikibase:BestRank; psv:${property} ${variable}Value].
+    ${variable}Value wikibase:timeValue ${variable};
+      wikibase:timePrecision ${variable}Precision.
+  }`;
+}
+
+// SPARQL for getting each of a subject's statements for a property as ?statement, along with its value
+// Deprecated statements and unknown values are left out, so dates can be read from the rest's qualifiers
+// Ex. wd:Q42, P39, ?position binds ?statement and ?position for each position held
+export function statementClause(subject: string, property: PID, variable: string): string {
+  return `${subject} p:${property} ?statement.
+  ?statement ps:${property} ${variable};
+    wikibase:rank ?rank.
+  FILTER(?rank != wikibase:DeprecatedRank)
+  FILTER(STRSTARTS(STR(${variable}), STR(wd:))) # Only entities, not unknown values`;
+}
+
+// SPARQL for getting a date qualifier of a statement, along with its precision
+// Ex. ?statement, P580, ?start binds ?start to 1990-01-01T00:00:00Z and ?startPrecision to 9 (year)
+export function qualifierDateClause(statement: string, property: PID, variable: string): string {
+  return `OPTIONAL {
+    ${statement} pqv:${property} ${variable}Value.
+    ${variable}Value wikibase:timeValue ${variable};
+      wikibase:timePrecision ${variable}Precision.
+  }`;
+}
+
+// Get a date bound by dateClause or qualifierDateClause, if there is one
+export function getDate(bindings: Row, name: string): PartialDate | undefined {
+  const [date] = getOptionalValues(bindings, name);
+  if (date === undefined) return undefined;
+  return parseDate(date, getTyped(bindings, `${name}Precision`, fromSparql.integer));
+}
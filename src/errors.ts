@@ -0,0 +1,40 @@
+// The one error type thrown by PGCite, tagged with what went wrong
+// engine: the query engine couldn't be created
+// network: the request failed, or the endpoint rejected it for a reason other than the query
+// query: the query is invalid, with the parser's or endpoint's explanation (no results isn't an error)
+// malformed: the response couldn't be read, or didn't have the expected bindings
+// rateLimited: the endpoint still answered 429 Too Many Requests after every retry
+// timeout: the request took longer than the client's timeout
+// fixture: a FixtureClient has no saved response for a query
+// notFound: an entity doesn't exist (only checked where asked for, ex. getAuthorInfo's checkExists)
+// url: a URL couldn't be parsed
+// invalidId: an entity, property, or other identifier couldn't be parsed
+// invalidLanguage: a language code isn't a plausible BCP 47 tag
+// invalidArgument: any other argument was out of range or couldn't be used
+export type PGCiteErrorKind =
+  | "engine"
+  | "network"
+  | "query"
+  | "malformed"
+  | "rateLimited"
+  | "timeout"
+  | "fixture"
+  | "notFound"
+  | "url"
+  | "invalidId"
+  | "invalidLanguage"
+  | "invalidArgument";
+
+export class PGCiteError extends Error {
+  kind: PGCiteErrorKind; // Ex. network
+
+  constructor(kind: PGCiteErrorKind, message: string, cause?: unknown) {
+    super(message, { cause });
+    this.name = "PGCiteError";
+    this.kind = kind;
+  }
+}
+
+export function errorMessage(error: unknown): string {
+  return error instanceof Error ? error.message : String(error);
+}
@@ -0,0 +1,61 @@
+import { describe, expect, test } from "bun:test";
+import { Field, Person } from "./authors.ts";
+import { parseDate } from "./dates.ts";
+import { toBibtex, toCslJson, toTurtle, toVcard } from "./export.ts";
+
+// Born only known to the year, died known to the day
+const person = new Person("Douglas Adams", undefined, "http://www.wikidata.org/entity/Q42", {
+  born: parseDate("1952-01-01T00:00:00Z", 9),
+  died: parseDate("2001-05-11T00:00:00Z", 11),
+});
+
+describe("toBibtex", () => {
+  test("writes only the known parts of each date", () => {
+    const bibtex = toBibtex(person, []);
+    expect(bibtex).toContain("  birthdate = {1952},");
+    expect(bibtex).toContain("  deathdate = {2001-05-11},");
+  });
+
+  test("escapes braces and backslashes so fields stay balanced", () => {
+    const odd = new Person("A} \\ {B & C", undefined, "http://www.wikidata.org/entity/Q42");
+    expect(toBibtex(odd, [])).toContain(
+      "  author = {A\\textbraceright{} \\textbackslash{} \\textbraceleft{}B \\& C},",
+    );
+  });
+});
+
+describe("toCslJson", () => {
+  test("writes only the known parts of each date", () => {
+    expect(toCslJson(person, []).issued).toEqual({ "date-parts": [[1952], [2001, 5, 11]] });
+  });
+});
+
+describe("toVcard", () => {
+  test("falls back to the date fields for a person without their own dates", () => {
+    const undated = new Person("Douglas Adams", undefined, "http://www.wikidata.org/entity/Q42");
+    const born = new Field(
+      "http://www.wikidata.org/prop/direct/P569",
+      "date of birth",
+      "1952-03-11T00:00:00Z",
+      "literal",
+    );
+    expect(toVcard(undated, [born])).toContain("\r\nBDAY:19520311\r\n");
+    expect(toVcard(person, [born])).toContain("\r\nBDAY:1952\r\n");
+  });
+});
+
+describe("toTurtle", () => {
+  const occupation = (id: string) => new Field(id, "occupation", "novelist", "literal");
+
+  test("writes Wikidata IRIs as prefixed names", () => {
+    const turtle = toTurtle(person, [occupation("http://www.wikidata.org/prop/direct/P106")]);
+    expect(turtle).toContain('\nwd:Q42\n  wdt:P106 "novelist" .\n');
+  });
+
+  test("writes another Wikibase's IRIs in full", () => {
+    const local = new Person("Douglas Adams", undefined, "http://localhost:8181/entity/Q1");
+    const turtle = toTurtle(local, [occupation("http://localhost:8181/prop/direct/P1")]);
+    expect(turtle).toContain("\n<http://localhost:8181/entity/Q1>\n");
+    expect(turtle).toContain('  <http://localhost:8181/prop/direct/P1> "novelist" .\n');
+  });
+});
@@ -0,0 +1,288 @@
+import type { Term } from "@rdfjs/types";
+import { type Field, type Person, splitName } from "./authors.ts";
+import { WikidataClient } from "./client.ts";
+import { type PartialDate, formatDate, isApproximate, isBce, parseDate } from "./dates.ts";
+import type { PID } from "./ids.ts";
+
+// Dates and external identifiers (ex. VIAF IDs) are literals, not entities, so exports that use them
+// need fields from getAuthorInfo with onlyWikidataEntities false
+
+// Braces and backslashes are written as commands instead, since BibTeX counts braces even after a
+// backslash (so \{ could unbalance the field), and a lone backslash would start a command
+const bibtexEscapes: Record<string, string> = {
+  "{": "\\textbraceleft{}",
+  "}": "\\textbraceright{}",
+  "\\": "\\textbackslash{}",
+};
+
+// Escape characters that are special to (La)TeX, ex. & -> \&
+function escapeBibtex(str: string): string {
+  return str.replace(/[{}\\&%$#_]/g, (char) => bibtexEscapes[char] ?? `\\${char}`);
+}
+
+// BibTeX fields that known Wikidata properties are mapped to
+const bibtexFields: Partial<Record<PID, string>> = {
+  P569: "birthdate", // date of birth
+  P570: "deathdate", // date of death
+  P106: "occupation",
+};
+
+// A person's date of birth (P569) or death (P570), from the person if they have it, or else the fields
+// The person's own dates know their precision, so the fields for them are only a fallback, read to
+// the day since they're xsd:dateTime, ex. 1952-03-11T00:00:00Z
+function lifespanDate(person: Person, fields: Field[], labelId: "P569" | "P570") {
+  const own = labelId === "P569" ? person.born : person.died;
+  if (own !== undefined) return own;
+  const value = fields.find((field) => field.labelId === labelId)?.value;
+  return value === undefined ? undefined : parseDate(value, 11);
+}
+
+// Format a date for BibTeX as ISO 8601, or for years BCE as a year, ex. 1952-03-11 or c. 800 BCE
+// ISO 8601 years before 1 CE are off by one from BCE years, and most BibTeX styles print them as-is
+function formatBibtexDate(date: PartialDate): string {
+  if (!isBce(date)) return formatDate(date);
+  return `${isApproximate(date) ? "c. " : ""}${-date.year} BCE`;
+}
+
+// Render a person as a BibTeX @misc entry, with their QID as the cite key
+// Fields with no known mapping are dropped, and multiple values for one field are comma-separated
+export function toBibtex(person: Person, fields: Field[]): string {
+  const mapped = new Map<string, string[]>(); // BibTeX field name -> values
+
+  const dates: Record<string, PartialDate | undefined> = {
+    birthdate: lifespanDate(person, fields, "P569"),
+    deathdate: lifespanDate(person, fields, "P570"),
+  };
+
+  for (const field of fields) {
+    const name = bibtexFields[field.labelId];
+    if (name === undefined || name in dates) continue;
+    mapped.set(name, [...(mapped.get(name) ?? []), field.value]);
+  }
+
+  for (const [name, date] of Object.entries(dates)) {
+    if (date !== undefined) mapped.set(name, [formatBibtexDate(date)]);
+  }
+
+  const lines = [
+    `  author = {${escapeBibtex(person.name)}},`,
+    `  title = {${escapeBibtex(person.name)}},`,
+    ...[...mapped].map(([name, values]) => `  ${name} = {${escapeBibtex(values.join(", "))}},`),
+    ...(person.description === undefined
+      ? []
+      : [`  note = {${escapeBibtex(person.description)}},`]),
+    `  url = {${person.idUrl.href}},`,
+  ];
+
+  return `@misc{${person.id},\n${lines.join("\n")}\n}`;
+}
+
+// The CSL date-parts of a date, as far as it's known, ex. { year: 1952, precision: "year" } -> [1952]
+// Years BCE are negative, as CSL expects, ex. { year: -800, precision: "year" } -> [-800]
+function knownParts({ year, month, day }: PartialDate): number[] {
+  return [year, month, day].filter((part) => part !== undefined);
+}
+
+// Render a person as a CSL-JSON item (https://citeproc-js.readthedocs.io/en/latest/csl-json/markup.html)
+// Uses the given name (P735) and family name (P734) fields if present, otherwise splits on the last space
+// Their lifespan is mapped to issued as a date range, with only the parts of each date that are known
+export function toCslJson(person: Person, fields: Field[]) {
+  const valueOf = (labelId: PID) => fields.find((field) => field.labelId === labelId)?.value;
+
+  const split = splitName(person.name);
+  const given = valueOf("P735") ?? split.given;
+  const family = valueOf("P734") ?? split.family;
+
+  const dates = [lifespanDate(person, fields, "P569"), lifespanDate(person, fields, "P570")].filter(
+    (date) => date !== undefined,
+  );
+  const lifespan = dates.map(knownParts);
+  const circa = dates.some(isApproximate);
+
+  return {
+    id: person.id,
+    type: "entry",
+    title: person.name,
+    author: [given && family ? { given, family } : { literal: person.name }],
+    ...(lifespan.length > 0 && { issued: { "date-parts": lifespan, ...(circa && { circa }) } }),
+    ...(person.description !== undefined && { note: person.description }),
+    URL: person.idUrl.href,
+  };
+}
+
+// Authority control identifiers that {{Authority control}} can show
+const authorityControlIds: PID[] = [
+  "P214", // VIAF ID
+  "P213", // ISNI
+];
+
+// Render a person as a {{Cite Q}} template for Wikipedia, ex. {{Cite Q|Q42}}
+// Adds {{Authority control}} (which reads its identifiers from Wikidata) if the fields include any
+export function toWikitextCite(person: Person, fields: Field[] = []): string {
+  const cite = `{{Cite Q|${person.id}}}`;
+  const hasAuthorityControl = fields.some((field) => authorityControlIds.includes(field.labelId));
+  return hasAuthorityControl ? `${cite}\n{{Authority control}}` : cite;
+}
+
+// Escape a value for a Markdown table cell, where pipes would start a new cell and newlines a new row
+function escapeMarkdownCell(str: string): string {
+  return str.replace(/\|/g, "\\|").replace(/\r?\n/g, " ");
+}
+
+// Render fields as a Markdown table with Property and Value columns
+// No fields gives just the header
+export function fieldsToMarkdown(fields: Field[]): string {
+  const rows = fields.map(
+    (field) => `| ${escapeMarkdownCell(field.label)} | ${escapeMarkdownCell(field.value)} |`,
+  );
+  return ["| Property | Value |", "|---|---|", ...rows].join("\n");
+}
+
+// Quote a CSV value if it contains a comma, quote, or newline, doubling any quotes (RFC 4180)
+function escapeCsv(str: string): string {
+  return /[",\r\n]/.test(str) ? `"${str.replace(/"/g, '""')}"` : str;
+}
+
+// Render people as CSV with id, name, description, and id_url columns, ex. for a spreadsheet
+// No people gives just the header
+export function authorsToCsv(people: Person[]): string {
+  const rows = people.map((person) =>
+    [person.id, person.name, person.description ?? "", person.idUrl.href].map(escapeCsv).join(","),
+  );
+  return ["id,name,description,id_url", ...rows].join("\r\n");
+}
+
+// Where the external identifiers sameAs links to can be looked up, by property
+const identifierUrls: Partial<Record<PID, (id: string) => string>> = {
+  P214: (id) => `https://viaf.org/viaf/${id}`, // VIAF ID
+  P213: (id) => `https://isni.org/isni/${id.replace(/\s/g, "")}`, // ISNI, ex. 0000 0001 2144 0811
+  P496: (id) => `https://orcid.org/${id}`, // ORCID iD
+  P244: (id) => `https://id.loc.gov/authorities/names/${id}`, // Library of Congress authority ID
+};
+
+// Render a person as schema.org JSON-LD (https://schema.org/Person), ex. for embedding in a web page
+// sameAs links to their Wikidata entity, and to any external identifiers in the fields
+export function toJsonLd(person: Person, fields: Field[] = []) {
+  const identifiers = fields.flatMap((field) => {
+    const toUrl = identifierUrls[field.labelId];
+    return toUrl === undefined ? [] : [toUrl(field.value)];
+  });
+
+  return {
+    "@context": "https://schema.org",
+    "@type": "Person",
+    name: person.name,
+    ...(person.description !== undefined && { description: person.description }),
+    ...(person.born !== undefined && { birthDate: formatDate(person.born) }),
+    ...(person.died !== undefined && { deathDate: formatDate(person.died) }),
+    ...(person.imageUrl !== undefined && { image: person.imageUrl.href }),
+    sameAs: [person.idUrl.href, ...new Set(identifiers)],
+  };
+}
+
+// Escape a vCard property value, ex. Adams, Douglas -> Adams\, Douglas (RFC 6350 section 3.4)
+function escapeVcard(str: string): string {
+  return str.replace(/([\\,;])/g, "\\$1").replace(/\r?\n/g, "\\n");
+}
+
+// Format a date for vCard, only as precisely as it's known, ex. 19520311, 1952-03, or 1952
+// Undefined for years BCE, which vCard dates can't represent
+function formatVcardDate({ year, month, day }: PartialDate): string | undefined {
+  if (year < 0) return undefined;
+  const pad = (part: number) => String(part).padStart(2, "0");
+  const yyyy = String(year).padStart(4, "0");
+  if (month === undefined) return yyyy;
+  return day === undefined ? `${yyyy}-${pad(month)}` : `${yyyy}${pad(month)}${pad(day)}`;
+}
+
+// vCard extension properties that external identifiers are mapped to, by property
+const vcardIdentifiers: Partial<Record<PID, string>> = {
+  P214: "X-VIAF", // VIAF ID
+  P213: "X-ISNI", // ISNI
+  P496: "X-ORCID", // ORCID iD
+  P244: "X-LCCN", // Library of Congress authority ID
+};
+
+// Render a person as a vCard 4.0 (RFC 6350), ex. for an address book
+// Their lifespan is BDAY and DEATHDATE (RFC 6474), from the person's dates or else the date fields
+export function toVcard(person: Person, fields: Field[] = []): string {
+  const vcardDate = (labelId: "P569" | "P570") => {
+    const date = lifespanDate(person, fields, labelId);
+    return date === undefined ? undefined : formatVcardDate(date);
+  };
+  const bday = vcardDate("P569");
+  const deathdate = vcardDate("P570");
+
+  const identifiers = fields.flatMap((field) => {
+    const name = vcardIdentifiers[field.labelId];
+    return name === undefined ? [] : [`${name}:${escapeVcard(field.value)}`];
+  });
+
+  const lines = [
+    "BEGIN:VCARD",
+    "VERSION:4.0",
+    `FN:${escapeVcard(person.name)}`,
+    ...(bday === undefined ? [] : [`BDAY:${bday}`]),
+    ...(deathdate === undefined ? [] : [`DEATHDATE:${deathdate}`]),
+    `URL:${person.idUrl.href}`,
+    ...new Set(identifiers),
+    "END:VCARD",
+  ];
+  return lines.join("\r\n");
+}
+
+const turtleEscapes: Record<string, string> = {
+  "\\": "\\\\",
+  '"': '\\"',
+  "\n": "\\n",
+  "\r": "\\r",
+  "\t": "\\t",
+};
+
+// Escape a string for a quoted Turtle literal, ex. "Hi" -> \"Hi\"
+function escapeTurtle(str: string): string {
+  return str.replace(/[\\"\n\r\t]/g, (char) => turtleEscapes[char]);
+}
+
+const xsdString = "http://www.w3.org/2001/XMLSchema#string";
+
+// Write a term in Turtle, using a prefixed name where one fits, ex. wd:Q5, "Adams"@en, or <https://...>
+function termToTurtle(term: Term, prefixes: Record<string, string>): string {
+  if (term.termType === "Literal") {
+    const quoted = `"${escapeTurtle(term.value)}"`;
+    if (term.language !== "") return `${quoted}@${term.language}`;
+    return term.datatype.value === xsdString ? quoted : `${quoted}^^<${term.datatype.value}>`;
+  }
+  if (term.termType === "BlankNode") return `_:${term.value.replace(/\W/g, "_")}`;
+  return iriToTurtle(term.value, prefixes);
+}
+
+// Write an IRI in Turtle, as a prefixed name if it's in one of the prefixes, ex. wd:Q5 or <https://...>
+function iriToTurtle(value: string, prefixes: Record<string, string>): string {
+  for (const [name, iri] of Object.entries(prefixes)) {
+    const local = value.slice(iri.length);
+    if (value.startsWith(iri) && /^[A-Za-z\d_-]+$/.test(local)) return `${name}:${local}`;
+  }
+  return `<${value}>`;
+}
+
+// Render a person's fields as RDF Turtle triples, ex. wd:Q42 wdt:P106 wd:Q6625963 .
+// The client's default prefixes are declared at the top, so triples read like Wikidata's own RDF
+// Fields made without a valueTerm (ex. by hand) become plain string literals of their value
+export function toTurtle(person: Person, fields: Field[] = []): string {
+  const prefixes = WikidataClient.defaultPrefixes;
+  const declarations = Object.entries(prefixes).map(([name, iri]) => `@prefix ${name}: <${iri}> .`);
+
+  const objects = fields.map((field) => {
+    const object =
+      field.valueTerm === undefined
+        ? `"${escapeTurtle(field.value)}"`
+        : termToTurtle(field.valueTerm, prefixes);
+    return `${iriToTurtle(field.labelIdUrl.href, prefixes)} ${object}`;
+  });
+  const unique = [...new Set(objects)];
+  if (unique.length === 0) return `${declarations.join("\n")}\n`;
+
+  const subject = iriToTurtle(person.idUrl.href, prefixes);
+  return `${declarations.join("\n")}\n\n${subject}\n  ${unique.join(" ;\n  ")} .\n`;
+}
@@ -0,0 +1,168 @@
+import type { BlankNode, Literal, NamedNode, Term } from "@rdfjs/types";
+import type { Logger, SparqlSource } from "./client.ts";
+import { PGCiteError } from "./errors.ts";
+import type { Row } from "./sparql.ts";
+
+// A bound term in the SPARQL 1.1 Query Results JSON Format
+// https://www.w3.org/TR/sparql11-results-json/#select-encode-terms
+export interface SparqlJsonTerm {
+  type: "uri" | "literal" | "typed-literal" | "bnode";
+  value: string;
+  "xml:lang"?: string;
+  datatype?: string;
+}
+
+// One result, ex. { "id": { "type": "uri", "value": "http://www.wikidata.org/entity/Q42" } }
+export type SparqlJsonBindings = Record<string, SparqlJsonTerm>;
+
+// The saved response to one query: either its results' bindings, or the whole results document
+// (as downloaded from https://query.wikidata.org/ with "JSON file"), or a boolean for ASK queries
+export type Fixture =
+  | SparqlJsonBindings[]
+  | { results: { bindings: SparqlJsonBindings[] } }
+  | { boolean: boolean };
+
+// Terms are equal if they're the same kind of term with the same value (and language and datatype)
+function termEquals(term: Term, other: Term | null | undefined): boolean {
+  if (other == null || other.termType !== term.termType || other.value !== term.value) return false;
+  if (term.termType !== "Literal" || other.termType !== "Literal") return true;
+  return other.language === term.language && other.datatype.value === term.datatype.value;
+}
+
+function namedNode(value: string): NamedNode {
+  const node: NamedNode = {
+    termType: "NamedNode",
+    value,
+    equals: (other) => termEquals(node, other),
+  };
+  return node;
+}
+
+// Convert a SPARQL JSON term into an RDF/JS term, like the ones the query engine gives
+function termFromJson(json: SparqlJsonTerm): Term {
+  if (json.type === "uri") return namedNode(json.value);
+
+  if (json.type === "bnode") {
+    const node: BlankNode = {
+      termType: "BlankNode",
+      value: json.value,
+      equals: (other) => termEquals(node, other),
+    };
+    return node;
+  }
+
+  // Literals with a language are rdf:langString, and untyped ones xsd:string
+  const language = json["xml:lang"] ?? "";
+  const datatype =
+    json.datatype ??
+    (language === ""
+      ? "http://www.w3.org/2001/XMLSchema#string"
+      : "http://www.w3.org/1999/02/22-rdf-syntax-ns#langString");
+  const literal: Literal = {
+    termType: "Literal",
+    value: json.value,
+    language,
+    datatype: namedNode(datatype),
+    equals: (other) => termEquals(literal, other),
+  };
+  return literal;
+}
+
+// Convert a saved response into rows, like the ones a WikidataClient gives
+// Throws a malformed PGCiteError for the response to an ASK query
+export function rowsFromFixture(fixture: Fixture): Row[] {
+  if ("boolean" in fixture) throw new PGCiteError("malformed", "Expected bindings, not a boolean!");
+  const results = Array.isArray(fixture) ? fixture : fixture.results.bindings;
+  return results.map((bindings) => {
+    const terms = new Map(
+      Object.entries(bindings).map(([name, json]): [string, Term] => [name, termFromJson(json)]),
+    );
+    return { get: (name) => terms.get(name) };
+  });
+}
+
+// Get the answer to an ASK query from a saved response
+// Throws a malformed PGCiteError for the response to any other query
+export function booleanFromFixture(fixture: Fixture): boolean {
+  if (!("boolean" in fixture)) {
+    throw new PGCiteError("malformed", "Expected a boolean, not bindings!");
+  }
+  return fixture.boolean;
+}
+
+// Answers queries from saved responses instead of an endpoint, ex. for reproducible tests and demos
+// Responses are keyed by query, as the query functions build it (without prefixes; see buildAuthorsQuery)
+// Leading and trailing whitespace is ignored when matching queries
+export class FixtureClient implements SparqlSource {
+  readonly fixtures: Map<string, Fixture>;
+  logger?: Logger; // Nothing is logged unless this is set
+  sanitize = false;
+
+  constructor(fixtures: Record<string, Fixture>) {
+    const entries = Object.entries(fixtures);
+    this.fixtures = new Map(entries.map(([query, fixture]) => [query.trim(), fixture] as const));
+  }
+
+  // Load fixtures from a JSON file of an object mapping queries to saved responses
+  static async fromFile(path: string): Promise<FixtureClient> {
+    return new FixtureClient(await Bun.file(path).json());
+  }
+
+  // Set where diagnostics go (ex. console), or undefined to stop logging
+  withLogger(logger: Logger | undefined): this {
+    this.logger = logger;
+    return this;
+  }
+
+  // Clean up markup in descriptions and values, like WikidataClient.withSanitize
+  withSanitize(sanitize = true): this {
+    this.sanitize = sanitize;
+    return this;
+  }
+
+  // Get the saved response to a query
+  // Throws a fixture PGCiteError if there isn't one, rather than returning no results
+  private getFixture(query: string): Fixture {
+    this.logger?.debug("SPARQL query:", query);
+
+    const fixture = this.fixtures.get(query.trim());
+    if (fixture === undefined) {
+      throw new PGCiteError("fixture", `No fixture for query: ${query.trim()}`);
+    }
+    return fixture;
+  }
+
+  async makeRequest(query: string): Promise<Row[]> {
+    const rows = rowsFromFixture(this.getFixture(query));
+    this.logger?.debug(`Got ${rows.length} bindings from fixtures`);
+    return rows;
+  }
+
+  async ask(query: string): Promise<boolean> {
+    return booleanFromFixture(this.getFixture(query));
+  }
+}
+
+// Answers queries with a function, and keeps every query it's asked, ex. for unit tests
+// Ex. new MockSource(() => []) gives no results for anything, and () => ({ boolean: true }) answers yes
+// To test failures, the function can throw (ex. a network PGCiteError)
+export class MockSource implements SparqlSource {
+  readonly queries: string[] = []; // In the order they were asked
+  logger?: Logger; // Nothing is logged unless this is set
+  sanitize = false;
+  private respond: (query: string) => Fixture | Promise<Fixture>;
+
+  constructor(respond: (query: string) => Fixture | Promise<Fixture>) {
+    this.respond = respond;
+  }
+
+  async makeRequest(query: string): Promise<Row[]> {
+    this.queries.push(query);
+    return rowsFromFixture(await this.respond(query));
+  }
+
+  async ask(query: string): Promise<boolean> {
+    this.queries.push(query);
+    return booleanFromFixture(await this.respond(query));
+  }
+}
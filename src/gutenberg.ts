@@ -0,0 +1,78 @@
+import type { SparqlSource } from "./client.ts";
+import { PGCiteError } from "./errors.ts";
+import { type QID, parseQID, parseUrl, qidFromUrl } from "./ids.ts";
+import { escapeSPARQL, getValues } from "./sparql.ts";
+
+// Project Gutenberg (https://www.gutenberg.org/) has separate Wikidata properties for its authors (P1938)
+// and its ebooks (P2034), and both are identifiers as Gutenberg gives them, ex. 53 or 1342
+
+// Gutenberg identifiers are positive integers with no leading zeros, ex. 53
+// Throws an invalidId PGCiteError for anything else, since they're put straight into a URL
+function parseGutenbergId(id: string): string {
+  const trimmed = id.trim();
+  if (!/^[1-9]\d*$/.test(trimmed)) {
+    throw new PGCiteError("invalidId", `'${id}' is not a Project Gutenberg ID!`);
+  }
+  return trimmed;
+}
+
+// The Project Gutenberg page listing an author's ebooks, ex. 53 -> https://www.gutenberg.org/ebooks/author/53
+// Throws a PGCiteError if the ID isn't numeric
+export function gutenbergAuthorUrl(id: string): URL {
+  return new URL(`https://www.gutenberg.org/ebooks/author/${parseGutenbergId(id)}`);
+}
+
+// The Project Gutenberg page for an ebook, with its free full text, ex. 1342 -> https://www.gutenberg.org/ebooks/1342
+// Throws a PGCiteError if the ID isn't numeric
+export function gutenbergEbookUrl(id: string): URL {
+  return new URL(`https://www.gutenberg.org/ebooks/${parseGutenbergId(id)}`);
+}
+
+// Get the Project Gutenberg author IDs (P1938) of a person, ex. Q7245 (Mark Twain) -> ["53"]
+// Usually one, but some people are split across several Gutenberg entries, and most have none
+export async function getGutenbergIds(client: SparqlSource, author: QID): Promise<string[]> {
+  const query = `
+SELECT DISTINCT
+  ?gutenbergId # Ex. 53
+WHERE {
+  wd:${parseQID(author)} wdt:P1938 ?gutenbergId.
+}`;
+
+  return (await client.makeRequest(query)).map((bindings) => getValues(bindings, "gutenbergId")[0]);
+}
+
+// Get the Project Gutenberg ebook ID (P2034) of a work, if it has one
+// Ex. Q170583 (Pride and Prejudice) -> "1342"
+export async function getGutenbergEbook(
+  client: SparqlSource,
+  work: QID,
+): Promise<string | undefined> {
+  const query = `
+SELECT
+  ?ebookId # Ex. 1342
+WHERE {
+  wd:${parseQID(work)} wdt:P2034 ?ebookId.
+}
+LIMIT 1`;
+
+  const [bindings] = await client.makeRequest(query);
+  return bindings === undefined ? undefined : getValues(bindings, "ebookId")[0];
+}
+
+// Find the Wikidata entity for a Project Gutenberg author ID (P1938), ex. 53 -> Q7245 (Mark Twain)
+// Undefined if no entity has that ID
+export async function qidFromGutenberg(
+  client: SparqlSource,
+  authorId: string,
+): Promise<QID | undefined> {
+  const query = `
+SELECT
+  ?id # Ex. Q7245
+WHERE {
+  ?id wdt:P1938 "${escapeSPARQL(authorId.trim())}".
+}
+LIMIT 1`;
+
+  const [bindings] = await client.makeRequest(query);
+  return bindings === undefined ? undefined : qidFromUrl(parseUrl(getValues(bindings, "id")[0]));
+}
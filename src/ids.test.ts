@@ -0,0 +1,42 @@
+import { describe, expect, test } from "bun:test";
+import { PGCiteError } from "./errors.ts";
+import { getLastSegment, parsePID, parseQID, qidFromUrl } from "./ids.ts";
+
+describe("getLastSegment", () => {
+  test("gets the last segment of the path", () => {
+    expect(getLastSegment(new URL("http://www.wikidata.org/entity/Q42"))).toBe("Q42");
+  });
+
+  test("is undefined for a URL with no path", () => {
+    expect(getLastSegment(new URL("https://example.com"))).toBeUndefined();
+  });
+
+  test("throws a url error for a segment that can't be decoded", () => {
+    let kind: string | undefined;
+    try {
+      getLastSegment(new URL("http://x.example/entity/%E0"));
+    } catch (error) {
+      if (error instanceof PGCiteError) kind = error.kind;
+    }
+    expect(kind).toBe("url");
+  });
+});
+
+describe("qidFromUrl", () => {
+  test("throws an invalidId error for a URL with no path", () => {
+    let kind: string | undefined;
+    try {
+      qidFromUrl(new URL("https://example.com"));
+    } catch (error) {
+      if (error instanceof PGCiteError) kind = error.kind;
+    }
+    expect(kind).toBe("invalidId");
+  });
+});
+
+describe("parseQID", () => {
+  test("keeps every digit of an ID too big for a number", () => {
+    expect(parseQID("Q12345678901234567890")).toBe("Q12345678901234567890");
+    expect(parsePID("wdt:P12345678901234567890")).toBe("P12345678901234567890");
+  });
+});
@@ -0,0 +1,70 @@
+import { PGCiteError, errorMessage } from "./errors.ts";
+
+// A Wikidata entity ID, ex. Q42
+export type QID = `Q${number}`;
+
+// A Wikidata property ID, ex. P106
+export type PID = `P${number}`;
+
+// Parse an entity ID from any of Q42, 42, wd:Q42, or https://www.wikidata.org/entity/Q42
+export function parseQID(input: string): QID {
+  const match = input
+    .trim()
+    .match(/^(?:(?:https?:\/\/www\.wikidata\.org\/(?:entity|wiki)\/|wd:)Q|Q?)([1-9]\d*)$/);
+  if (match === null) throw new PGCiteError("invalidId", `'${input}' is not a Wikidata entity ID!`);
+  return `Q${match[1]}` as QID; // Not through Number, which would round IDs past 2^53
+}
+
+// Parse a property ID from any of P106, 106, wdt:P106, or http://www.wikidata.org/prop/direct/P106
+// (or the other wikidata.org/prop/... namespaces and their prefixes)
+export function parsePID(input: string): PID {
+  const match = input
+    .trim()
+    .match(
+      /^(?:(?:https?:\/\/www\.wikidata\.org\/(?:prop\/(?:[a-z-]+\/)*|entity\/|wiki\/Property:)|(?:wdt|p|ps|pq|wd):)P|P?)([1-9]\d*)$/,
+    );
+  if (match === null) {
+    throw new PGCiteError("invalidId", `'${input}' is not a Wikidata property ID!`);
+  }
+  return `P${match[1]}` as PID;
+}
+
+// Get the last segment of a URL's path, ex. http://www.wikidata.org/entity/Q42 -> Q42
+// Returns undefined if there isn't one, ex. https://example.com
+// Throws a PGCiteError if it can't be decoded, ex. http://x.example/entity/%E0 (half a character)
+export function getLastSegment(url: URL): string | undefined {
+  const segment = url.pathname.split("/").pop();
+  if (segment === undefined || segment === "") return undefined;
+  try {
+    return decodeURIComponent(segment);
+  } catch (error) {
+    const message = `'${url.href}' has an invalid escape: ${errorMessage(error)}`;
+    throw new PGCiteError("url", message, error);
+  }
+}
+
+// Get the entity ID an entity URL points to, ex. http://www.wikidata.org/entity/Q42 -> Q42
+export function qidFromUrl(url: URL): QID {
+  const segment = getLastSegment(url);
+  if (segment === undefined) throw new PGCiteError("invalidId", `'${url.href}' has no entity ID!`);
+  return parseQID(segment);
+}
+
+// Get the property ID a property URL points to, ex. http://www.wikidata.org/prop/direct/P106 -> P106
+// Goes by the last segment of the path, so it works for any Wikibase, not just Wikidata
+export function pidFromUrl(url: URL): PID {
+  const segment = getLastSegment(url);
+  if (segment === undefined) {
+    throw new PGCiteError("invalidId", `'${url.href}' has no property ID!`);
+  }
+  return parsePID(segment);
+}
+
+// Parse a URL, throwing a PGCiteError rather than a TypeError if it's invalid
+export function parseUrl(input: string): URL {
+  try {
+    return new URL(input);
+  } catch (error) {
+    throw new PGCiteError("url", `'${input}' is not a valid URL: ${errorMessage(error)}`, error);
+  }
+}
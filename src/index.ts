@@ -0,0 +1,127 @@
+export {
+  Field,
+  Person,
+  buildAuthorInfoQuery,
+  buildAuthorsQuery,
+  buildCountAuthorsQuery,
+  countAuthors,
+  disambiguate,
+  entityExists,
+  getAliases,
+  getAuthorIdentifiers,
+  getAuthorInfo,
+  getAuthorInfoBatch,
+  getAuthors,
+  getAuthorsMulti,
+  getAuthorsPage,
+  getAuthorsStream,
+  getAwards,
+  getBirthplace,
+  getCitizenships,
+  getCoauthors,
+  getEducation,
+  getEmployers,
+  getGender,
+  getInfluences,
+  getLanguages,
+  getLinkedEntities,
+  getNameParts,
+  getOccupations,
+  getPerson,
+  getPositions,
+  getWikipediaUrl,
+  groupFields,
+  preferredOnly,
+  searchAuthors,
+  splitName,
+  withoutDeprecated,
+} from "./authors.ts";
+export type {
+  AuthorInfoOptions,
+  AuthorityIds,
+  AuthorPage,
+  AuthorSearchOptions,
+  AuthorSort,
+  Award,
+  Education,
+  FieldDetails,
+  GroupedField,
+  Influences,
+  Languages,
+  LinkedEntity,
+  MatchMode,
+  NameParts,
+  PersonDetails,
+  Position,
+  Qualifier,
+  SearchResult,
+  SortOrder,
+  StatementRank,
+  ValueKind,
+} from "./authors.ts";
+export { PersonCache } from "./cache.ts";
+export { WikidataClient } from "./client.ts";
+export type { Logger, SparqlSource } from "./client.ts";
+export {
+  dateClause,
+  formatDate,
+  formatYear,
+  getDate,
+  isApproximate,
+  isBce,
+  parseDate,
+  qualifierDateClause,
+  statementClause,
+} from "./dates.ts";
+export type { DatePrecision, PartialDate } from "./dates.ts";
+export {
+  authorsToCsv,
+  fieldsToMarkdown,
+  toBibtex,
+  toCslJson,
+  toJsonLd,
+  toTurtle,
+  toVcard,
+  toWikitextCite,
+} from "./export.ts";
+export { PGCiteError, errorMessage } from "./errors.ts";
+export type { PGCiteErrorKind } from "./errors.ts";
+export { FixtureClient, MockSource, booleanFromFixture, rowsFromFixture } from "./fixtures.ts";
+export type { Fixture, SparqlJsonBindings, SparqlJsonTerm } from "./fixtures.ts";
+export {
+  getGutenbergEbook,
+  getGutenbergIds,
+  gutenbergAuthorUrl,
+  gutenbergEbookUrl,
+  qidFromGutenberg,
+} from "./gutenberg.ts";
+export {
+  getLastSegment,
+  parsePID,
+  parseQID,
+  parseUrl,
+  pidFromUrl,
+  qidFromUrl,
+} from "./ids.ts";
+export type { PID, QID } from "./ids.ts";
+export { getPlace, parsePoint } from "./places.ts";
+export type { Place } from "./places.ts";
+export {
+  escapeSPARQL,
+  fromSparql,
+  getOptionalValues,
+  getTyped,
+  getValues,
+  parseLanguage,
+} from "./sparql.ts";
+export type { FromSparql, Language, Row } from "./sparql.ts";
+export { sanitizeText, truncateDescription } from "./text.ts";
+export {
+  Work,
+  getNotableWorks,
+  getWorkByIsbn,
+  getWorkGenres,
+  getWorks,
+  getWorksByTitle,
+} from "./works.ts";
+export type { GenreProperty, WorkDetails } from "./works.ts";
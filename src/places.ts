@@ -0,0 +1,37 @@
+import { type QID, parseUrl, qidFromUrl } from "./ids.ts";
+import { type Row, getOptionalValues } from "./sparql.ts";
+
+// A place with its coordinates (P625), ex. { name: "Cambridge", id: "Q350", lat: 52.205, lon: 0.119 }
+export interface Place {
+  name: string;
+  id: QID;
+  lat: number; // Degrees north
+  lon: number; // Degrees east
+}
+
+// Parse a coordinate location, which Wikidata gives as a WKT point with longitude first
+// Ex. Point(0.119 52.205) -> { lat: 52.205, lon: 0.119 }
+// Returns undefined for values that can't be parsed, including points on other globes (ex. the Moon)
+export function parsePoint(value: string): { lat: number; lon: number } | undefined {
+  const match = value.match(/^Point\((\S+) (\S+)\)$/i);
+  if (match === null) return undefined;
+
+  const [lon, lat] = match.slice(1).map(Number);
+  return Number.isNaN(lon) || Number.isNaN(lat) ? undefined : { lat, lon };
+}
+
+// Get a place bound as ?name, with its label as ?nameLabel and coordinates as ?nameCoordinates
+// Returns undefined if any of them are missing
+export function getPlace(bindings: Row, name: string): Place | undefined {
+  const [place, label, coordinates] = getOptionalValues(
+    bindings,
+    name,
+    `${name}Label`,
+    `${name}Coordinates`,
+  );
+  if (place === undefined || label === undefined || coordinates === undefined) return undefined;
+
+  const point = parsePoint(coordinates);
+  if (point === undefined) return undefined;
+  return { name: label, id: qidFromUrl(parseUrl(place)), ...point };
+}
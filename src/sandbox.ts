@@ -1,148 +1,253 @@
 #! /usr/bin/env -S bun run
 
-import {} from "node:readline";
+import { createInterface } from "node:readline";
+import { parseArgs } from "node:util";
 import {} from "@citation-js/plugin-wikidata";
-import { QueryEngine } from "@comunica/query-sparql";
-import type { Bindings } from "@rdfjs/types";
-
-// https://stackoverflow.com/questions/29601839/standard-regex-to-prevent-sparql-injection/55726984#55726984
-function escapeSPARQL(str: string): string {
-  return str.replace(/(["'\\])/g, "\\$1");
+import type { AuthorSort, Field, Language, Person, QID, SparqlSource } from "./index.ts";
+import {
+  FixtureClient,
+  PGCiteError,
+  WikidataClient,
+  authorsToCsv,
+  buildAuthorInfoQuery,
+  buildAuthorsQuery,
+  buildCountAuthorsQuery,
+  countAuthors,
+  errorMessage,
+  fieldsToMarkdown,
+  getAuthorInfo,
+  getAuthors,
+  parseLanguage,
+  parseQID,
+  toCslJson,
+  truncateDescription,
+} from "./index.ts";
+
+const formats = ["text", "json", "csl", "markdown", "csv"];
+const sorts = ["none", "sitelinks"];
+const infoFormats = ["text", "json", "markdown"]; // Formats that don't need a Person
+
+const usage = `Usage: sandbox.ts [options] author <name>
+       sandbox.ts [options] info <qid>
+       sandbox.ts [options] --stdin < qids.txt
+
+Options:
+  --format <format>  One of ${formats.join(", ")} (default text)
+  --lang <lang>      Language of labels and descriptions (default en)
+  --limit <n>        Maximum number of authors to find (default unlimited)
+  --sort <sort>      One of ${sorts.join(", ")}; sitelinks is most notable first (default none)
+  --count            Print only the number of authors found, instead of the authors
+  --max-desc <n>     Shorten descriptions in text output to n characters (default unlimited)
+  --stdin            Show info for each newline-separated QID read from stdin
+  --dry-run          Print the SPARQL queries instead of making them
+  --fixtures <file>  Answer queries from saved responses in a JSON file, instead of Wikidata
+  --output <file>    Write the output to a file (replacing it) instead of printing it
+  --no-color         Don't color text output (it's only colored when printing to a terminal)`;
+
+const { values: args, positionals } = parseArgs({
+  options: {
+    format: { type: "string", default: "text" }, // One of formats
+    lang: { type: "string", default: "en" },
+    limit: { type: "string" },
+    sort: { type: "string", default: "none" }, // One of sorts
+    count: { type: "boolean", default: false },
+    "max-desc": { type: "string" },
+    stdin: { type: "boolean", default: false }, // Read newline-separated QIDs from stdin
+    "dry-run": { type: "boolean", default: false },
+    fixtures: { type: "string" },
+    output: { type: "string" },
+    "no-color": { type: "boolean", default: false },
+  },
+  allowPositionals: true,
+});
+
+// Print an error and the usage, then exit
+function fail(message: string): never {
+  console.error(`${message}\n\n${usage}`);
+  process.exit(1);
 }
 
-// Make a request to the Wikidata SPAQL API, using a given SPARQL query (as it would be entered in https://query.wikidata.org/)
-// Returns an array of bindings (https://comunica.dev/docs/query/getting_started/query_app/#3-3-consuming-binding-results-as-an-array)
-async function makeRequest(query: string) {
-  const queryWithPrefixes = `
-PREFIX wikibase: <http://wikiba.se/ontology#>
-PREFIX wd: <http://www.wikidata.org/entity/>
-PREFIX wdt: <http://www.wikidata.org/prop/direct/>
-PREFIX p: <http://www.wikidata.org/prop/>
-PREFIX ps: <http://www.wikidata.org/prop/statement/>
-PREFIX bd: <http://www.bigdata.com/rdf#>
-${query}`;
-
-  // console.log(queryWithPrefixes);
-
-  const bindingsStream = await new QueryEngine().queryBindings(queryWithPrefixes, {
-    sources: ["https://query.wikidata.org/sparql"],
-  });
-
-  return await bindingsStream
-    .on("error", (error) => {
-      console.error(error);
-    })
-    .toArray();
+const [command, ...rest] = positionals;
+
+if (!formats.includes(args.format)) fail(`Unknown format '${args.format}'!`);
+if (!sorts.includes(args.sort)) fail(`Unknown sort '${args.sort}'!`);
+if (args.limit !== undefined && !/^\d+$/.test(args.limit)) {
+  fail("--limit must be a non-negative integer!");
+}
+if (args["max-desc"] !== undefined && !/^\d+$/.test(args["max-desc"])) {
+  fail("--max-desc must be a non-negative integer!");
 }
+if (command !== "author" && !infoFormats.includes(args.format)) {
+  fail(`Format '${args.format}' needs the author command!`);
+}
+if (args.count && command !== "author") fail("--count needs the author command!");
 
-class Person {
-  name: string; // Ex. Douglas Adams
-  description: string; // Ex. English author and humourist (1952–2001)
-  id: string; // Ex. https://www.wikidata.org/entity/Q42
-  idUrl: URL; // Ex. Q42
-
-  constructor(name: string, description: string, id: string) {
-    this.name = name;
-    this.description = description;
-    this.idUrl = new URL(id);
-    this.id = id.replace(/.*\/entity\//, "");
-  }
+const output: string[] = []; // Lines to write to the --output file once everything's done
 
-  toString() {
-    return `${this.id}: ${this.name} (${this.description})`;
-  }
+// Print text, or keep it for the --output file
+function write(text: string) {
+  if (args.output === undefined) console.log(text);
+  else output.push(text);
 }
 
-// Get a list of authors with an exact name (e.g. "Douglas Adams")
-async function getAuthors(name: string): Promise<Person[]> {
-  const query = `
-SELECT
-  ?id          # Ex. Q42
-  ?name        # Ex. Douglas Adams
-  ?description # Ex. English author and humourist (1952–2001)
-WHERE {
-  VALUES ?name {
-    """${escapeSPARQL(name)}"""@en
+// Write everything kept for the --output file, replacing whatever was in it
+async function saveOutput() {
+  if (args.output === undefined) return;
+  try {
+    await Bun.write(args.output, `${output.join("\n")}\n`);
+  } catch (error) {
+    console.error(`Couldn't write to '${args.output}': ${errorMessage(error)}`);
+    process.exit(1);
   }
+}
 
-  ?id wdt:P31 wd:Q5;                 # The ID of an instance of human,
-    rdfs:label ?name;                # ...whose entity label matches ?name,
-    schema:description ?description. # ...and get their single-sentence entity description
+// Print a list of results as text (one per line) or JSON
+function print<T extends object>(results: T[], toText: (result: T) => string = String) {
+  const text = results.map(toText).join("\n");
+  write(args.format === "text" ? text : JSON.stringify(results, null, 2));
+}
 
-  FILTER((LANG(?name)) = "en")        # Only names in English
-  FILTER((LANG(?description)) = "en") # Only descriptions in English
-}`;
+// Colors are only for people reading the output, not when it's piped (see also https://no-color.org/)
+const useColor =
+  process.stdout.isTTY &&
+  args.output === undefined &&
+  process.env.NO_COLOR === undefined &&
+  !args["no-color"];
 
-  const result: Person[] = (await makeRequest(query)).map((bindings) => {
-    const values: string[] = getValues(bindings, "name", "description", "id");
-    return new Person(...(values as [string, string, string]));
-  });
+// Wrap text in an ANSI style, ex. 1 for bold, 2 for dim, or 90 for gray
+function style(code: number, text: string): string {
+  return useColor ? `\x1b[${code}m${text}\x1b[0m` : text;
+}
 
-  return result;
+// Ex. Douglas Adams (English author and humourist (1952–2001)) [Q42], with the name in bold
+// Descriptions are shortened to --max-desc characters, if it's given, and left out if there isn't one
+function personToText(person: Person): string {
+  const maxDesc = args["max-desc"];
+  const shortened =
+    maxDesc === undefined || person.description === undefined
+      ? person.description
+      : truncateDescription(person.description, Number(maxDesc));
+  const description = shortened === undefined ? "" : ` ${style(90, `(${shortened})`)}`;
+  return `${style(1, person.name)}${description} ${style(2, `[${person.id}]`)}`;
 }
 
-class Field {
-  value: string; // Ex. novelist
-  label: string; // Ex. occupation
-  labelId: string; // Ex. P106
-  labelIdUrl: URL; // Ex. https://www.wikidata.org/prop/direct/P106
-
-  constructor(labelId: string, label: string, value: string) {
-    this.value = value;
-    this.label = label;
-    this.labelId = labelId.replace(/.*\/direct\//, "");
-    this.labelIdUrl = new URL(labelId);
-  }
+// Ex. occupation: novelist, with the label in bold
+function fieldToText(field: Field): string {
+  return `${style(1, field.label)}: ${field.value}`;
+}
 
-  toString() {
-    return `${this.label}: ${this.value}`;
-  }
+const client = WikidataClient.fromEnv();
+const source: SparqlSource =
+  args.fixtures === undefined ? client : await FixtureClient.fromFile(args.fixtures);
+let lang: Language;
+try {
+  lang = parseLanguage(args.lang);
+} catch (error) {
+  fail(errorMessage(error));
 }
 
-// Get information about a given author, using an exact ID (ex. Q42)
-// onlyWikidataEntities filters results to only those with Wikidata entries (not literal values)
-async function getAuthorInfo(id: `Q${number}`, onlyWikidataEntities = true): Promise<Field[]> {
-  const query = `
-SELECT DISTINCT
-  ?propID     # Ex. P734
-  ?propLabel  # Ex. family name
-  ?value      # Ex. Q351735
-  ?valueLabel # Ex. Adams
-WHERE {
-  VALUES ?target {
-    wd:${id}
+// Find authors by name and print them in the chosen format
+async function showAuthors(name: string) {
+  const limit = args.limit === undefined ? undefined : Number(args.limit);
+  const options = { lang, limit, sortBy: args.sort as AuthorSort };
+  if (args["dry-run"]) {
+    const buildQuery = args.count ? buildCountAuthorsQuery : buildAuthorsQuery;
+    write(client.addPrefixes(buildQuery(name, options, source)));
+    return;
   }
 
-  ?target ?propID ?value.
+  if (args.count) {
+    const count = await countAuthors(source, name, options);
+    write(args.format === "json" ? JSON.stringify({ count }) : String(count));
+    return;
+  }
 
-  ?prop wikibase:directClaim ?propID.
+  const authors = await getAuthors(source, name, options);
+
+  switch (args.format) {
+    case "csl": {
+      // Literal values are needed too, since dates aren't entities
+      const items = [];
+      for (const author of authors) {
+        const fields = await getAuthorInfo(source, author.id, {
+          onlyWikidataEntities: false,
+          languages: [lang],
+        });
+        items.push(toCslJson(author, fields));
+      }
+      print(items);
+      break;
+    }
+    case "markdown":
+      write(authors.map((author) => `- ${author}`).join("\n"));
+      break;
+    case "csv":
+      write(authorsToCsv(authors));
+      break;
+    default:
+      print(authors, personToText);
+  }
+}
 
-  # Filters results to only those with Wikidata entries
-  # Ex. Q84 but not douglasadams
-  ${onlyWikidataEntities ? "#" : ""} FILTER(CONTAINS(STR(?value), "/entity/Q"))
+// Get the properties of an entity and print them in the chosen format
+async function showInfo(id: QID) {
+  const options = { languages: [lang], checkExists: true };
+  if (args["dry-run"]) {
+    write(client.addPrefixes(buildAuthorInfoQuery(id, options)));
+    return;
+  }
 
-  # Fetchs the label for every ?variable, the result of which is stored in ?variableLabel
-  SERVICE wikibase:label { bd:serviceParam wikibase:language "[AUTO_LANGUAGE],en". }
+  const fields = await getAuthorInfo(source, id, options);
+  if (args.format === "markdown") write(fieldsToMarkdown(fields));
+  else print(fields, fieldToText);
 }
-ORDER BY DESC(?propID) # Doesn't actually sort correctly because props aren't 0-padded`;
-
-  const result: Field[] = (await makeRequest(query)).map((bindings) => {
-    const values: string[] = getValues(bindings, "propID", "propLabel", "valueLabel");
-    return new Field(...(values as [string, string, string]));
-  });
 
-  return result;
+if (args.stdin) {
+  // Bad lines are reported and skipped, so one typo doesn't abort a whole batch
+  for await (const line of createInterface({ input: process.stdin })) {
+    if (line.trim() === "") continue;
+
+    let id: QID;
+    try {
+      id = parseQID(line);
+    } catch (error) {
+      console.error(errorMessage(error));
+      continue;
+    }
+
+    // Including QIDs that don't exist, and failed requests
+    try {
+      write(`# ${id}`);
+      await showInfo(id);
+    } catch (error) {
+      console.error(errorMessage(error));
+    }
+  }
+  await saveOutput();
+  process.exit();
 }
 
-// Get a list of values for the given binding names
-function getValues(bindings: Bindings, ...names: string[]) {
-  return names.map((name: string) => {
-    const value = bindings.get(name)?.value;
-    if (value === undefined) throw new Error(`Binding '${name}' is undefined!`);
-    return value;
-  });
+// Bad IDs are a usage mistake, but anything else (ex. a failed request) is only reported
+try {
+  switch (command) {
+    case "author":
+      if (rest.length === 0) fail("Missing the author's name!");
+      await showAuthors(rest.join(" ")); // So quoting the name is optional
+      break;
+    case "info":
+      if (rest.length !== 1) fail("Expected one QID!");
+      await showInfo(parseQID(rest[0]));
+      break;
+    case undefined:
+      fail("Missing a command!");
+      break;
+    default:
+      fail(`Unknown command '${command}'!`);
+  }
+} catch (error) {
+  if (error instanceof PGCiteError && error.kind === "invalidId") fail(error.message);
+  console.error(errorMessage(error));
+  process.exit(1);
 }
 
-console.log(await getAuthors("William Carpenter"), await getAuthorInfo("Q8006577"));
-// console.log(await getAuthors("Douglas Adams"));
-// console.log(await getAuthorInfo("Q42"));
+await saveOutput();
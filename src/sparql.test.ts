@@ -0,0 +1,45 @@
+import { describe, expect, test } from "bun:test";
+import { PGCiteError } from "./errors.ts";
+import { escapeSPARQL, parseLanguage } from "./sparql.ts";
+
+// The kind of PGCiteError a function throws, or undefined if it doesn't throw one
+function errorKind(fn: () => unknown): string | undefined {
+  try {
+    fn();
+  } catch (error) {
+    if (error instanceof PGCiteError) return error.kind;
+  }
+  return undefined;
+}
+
+describe("escapeSPARQL", () => {
+  test("escapes each metacharacter", () => {
+    expect(escapeSPARQL('say "hi"')).toBe('say \\"hi\\"');
+    expect(escapeSPARQL("O'Brien")).toBe("O\\'Brien");
+    expect(escapeSPARQL("back\\slash")).toBe("back\\\\slash");
+    expect(escapeSPARQL("one\ntwo")).toBe("one\\ntwo");
+    expect(escapeSPARQL("one\rtwo")).toBe("one\\rtwo");
+    expect(escapeSPARQL("one\ttwo")).toBe("one\\ttwo");
+  });
+
+  test("leaves other text alone", () => {
+    expect(escapeSPARQL("Gabriel García Márquez")).toBe("Gabriel García Márquez");
+  });
+
+  test("throws for control characters it can't escape", () => {
+    expect(errorKind(() => escapeSPARQL("a\u0000b"))).toBe("invalidArgument");
+    expect(errorKind(() => escapeSPARQL("a\u007fb"))).toBe("invalidArgument");
+  });
+});
+
+describe("parseLanguage", () => {
+  test("accepts BCP 47 tags", () => {
+    for (const lang of ["en", "de", "zh-hans", "sr-latn"]) expect(parseLanguage(lang)).toBe(lang);
+  });
+
+  test("rejects anything that could break out of a query", () => {
+    for (const lang of ['en" }', "en }", "en fr", ""]) {
+      expect(errorKind(() => parseLanguage(lang))).toBe("invalidLanguage");
+    }
+  });
+});
@@ -0,0 +1,112 @@
+import type { Term } from "@rdfjs/types";
+import { PGCiteError } from "./errors.ts";
+import { parseUrl } from "./ids.ts";
+
+// Escape sequences for characters that would end or corrupt a quoted SPARQL string
+// https://www.w3.org/TR/sparql11-query/#grammarEscapes
+const sparqlEscapes: Record<string, string> = {
+  '"': '\\"',
+  "'": "\\'",
+  "\\": "\\\\",
+  "\n": "\\n",
+  "\r": "\\r",
+  "\t": "\\t",
+};
+
+// Escape a string for use inside a quoted SPARQL literal
+// https://stackoverflow.com/questions/29601839/standard-regex-to-prevent-sparql-injection/55726984#55726984
+// Throws for control characters other than newlines and tabs, since SPARQL strings have no escape for them
+export function escapeSPARQL(str: string): string {
+  for (const char of str) {
+    const code = char.charCodeAt(0);
+    if ((code < 0x20 || code === 0x7f) && !(char in sparqlEscapes)) {
+      const codePoint = `U+${code.toString(16).padStart(4, "0")}`;
+      throw new PGCiteError("invalidArgument", `Can't escape control character ${codePoint}!`);
+    }
+  }
+
+  return str.replace(/["'\\\n\r\t]/g, (char) => sparqlEscapes[char]);
+}
+
+// A language code that's been checked by parseLanguage, so it's safe to put in a query
+export type Language = string & { readonly __brand: "Language" };
+
+// Parse a language code that's a plausible BCP 47 tag (ex. en, de, zh-hans)
+// Throws for anything else, including quotes, braces, and spaces that could break out of a query
+export function parseLanguage(lang: string): Language {
+  if (!/^[a-z]{2,8}(-[a-z0-9]{1,8})*$/i.test(lang)) {
+    throw new PGCiteError("invalidLanguage", `Invalid language code '${lang}'!`);
+  }
+  return lang as Language;
+}
+
+// One result of a query, as bindings from variable names to terms
+// Only what PGCite reads, so results don't have to come from the query engine (ex. a FixtureClient)
+export interface Row {
+  get(name: string): Term | undefined;
+}
+
+// Get a list of values for the given binding names
+export function getValues(bindings: Row, ...names: string[]) {
+  return names.map((name: string) => {
+    const value = bindings.get(name)?.value;
+    if (value === undefined) throw new PGCiteError("malformed", `Binding '${name}' is undefined!`);
+    return value;
+  });
+}
+
+// Get a list of values for the given binding names, with undefined for any that aren't bound
+// For bindings from OPTIONAL clauses, which may be missing
+export function getOptionalValues(bindings: Row, ...names: string[]) {
+  return names.map((name: string) => bindings.get(name)?.value);
+}
+
+// Converts a bound RDF term into a value, throwing if the term isn't of the right type
+export type FromSparql<T> = (term: Term) => T;
+
+const xsd = "http://www.w3.org/2001/XMLSchema#";
+
+// Converters for getTyped, ex. getTyped(bindings, "count", fromSparql.integer)
+export const fromSparql = {
+  // An xsd:integer literal, ex. "42"^^xsd:integer -> 42
+  integer: (term: Term): number => {
+    if (term.termType !== "Literal" || term.datatype.value !== `${xsd}integer`) {
+      throw new PGCiteError("malformed", `'${term.value}' is not an xsd:integer!`);
+    }
+    return Number(term.value);
+  },
+
+  // An xsd:dateTime literal, ex. "1952-03-11T00:00:00Z"^^xsd:dateTime -> Date
+  // Wikidata dates carry a precision, so prefer getDate (in dates.ts) for those
+  dateTime: (term: Term): Date => {
+    const date = new Date(term.value);
+    const isDateTime = term.termType === "Literal" && term.datatype.value === `${xsd}dateTime`;
+    if (!isDateTime || Number.isNaN(date.getTime())) {
+      throw new PGCiteError("malformed", `'${term.value}' is not an xsd:dateTime!`);
+    }
+    return date;
+  },
+
+  // A URI (rather than a literal), ex. <http://www.wikidata.org/entity/Q42> -> URL
+  uri: (term: Term): URL => {
+    if (term.termType !== "NamedNode") {
+      throw new PGCiteError("malformed", `'${term.value}' is not a URI!`);
+    }
+    return parseUrl(term.value);
+  },
+
+  // A literal of any type, as a string
+  literal: (term: Term): string => {
+    if (term.termType !== "Literal") {
+      throw new PGCiteError("malformed", `'${term.value}' is not a literal!`);
+    }
+    return term.value;
+  },
+} satisfies Record<string, FromSparql<unknown>>;
+
+// Get the value of a binding, converted by its type
+export function getTyped<T>(bindings: Row, name: string, convert: FromSparql<T>): T {
+  const term = bindings.get(name);
+  if (term === undefined) throw new PGCiteError("malformed", `Binding '${name}' is undefined!`);
+  return convert(term);
+}
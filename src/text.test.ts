@@ -0,0 +1,13 @@
+import { describe, expect, test } from "bun:test";
+import { sanitizeText } from "./text.ts";
+
+describe("sanitizeText", () => {
+  test("decodes entities", () => {
+    expect(sanitizeText("Pride &amp; Prejudice")).toBe("Pride & Prejudice");
+    expect(sanitizeText("&lt;3")).toBe("<3");
+  });
+
+  test("strips simple tags", () => {
+    expect(sanitizeText("<i>Dune</i> by Frank Herbert")).toBe("Dune by Frank Herbert");
+  });
+});
@@ -0,0 +1,48 @@
+import { PGCiteError } from "./errors.ts";
+
+const namedEntities: Record<string, string> = {
+  amp: "&",
+  lt: "<",
+  gt: ">",
+  quot: '"',
+  apos: "'",
+  nbsp: "\u00a0",
+};
+
+// Decode HTML entities, ex. Pride &amp; Prejudice -> Pride & Prejudice, or &#233; -> é
+// Unknown named entities (and numbers that aren't code points) are left as they are
+function decodeEntities(text: string): string {
+  return text.replace(/&(#x[\da-f]+|#\d+|[a-z]+);/gi, (entity, name: string) => {
+    if (!name.startsWith("#")) return namedEntities[name.toLowerCase()] ?? entity;
+    const codePoint =
+      name[1].toLowerCase() === "x" ? Number.parseInt(name.slice(2), 16) : Number(name.slice(1));
+    return codePoint <= 0x10ffff ? String.fromCodePoint(codePoint) : entity;
+  });
+}
+
+// Clean up stray markup in a value meant as plain text
+// Strips HTML tags (ex. <i>Dune</i> -> Dune) and wiki markup (ex. [[Dune (novel)|Dune]] or ''Dune'' -> Dune),
+// then decodes entities, so encoded text like &lt;3 becomes <3 rather than being stripped
+export function sanitizeText(text: string): string {
+  const stripped = text
+    .replace(/<\/?[a-z][^<>]*>/gi, "")
+    .replace(/\[\[(?:[^[\]|]*\|)?([^[\]]*)\]\]/g, "$1")
+    .replace(/'{2,}/g, "");
+  return decodeEntities(stripped).trim();
+}
+
+// Shorten a description to at most maxChars characters, ending in an ellipsis if anything was cut
+// Counts code points rather than UTF-16 units, so accented letters and emoji are never split
+// Ex. truncateDescription("English author and humourist (1952–2001)", 15) -> English author…
+// Throws a PGCiteError if maxChars isn't a non-negative integer
+export function truncateDescription(description: string, maxChars: number): string {
+  if (!Number.isInteger(maxChars) || maxChars < 0) {
+    throw new PGCiteError("invalidArgument", "Maximum length must be a non-negative integer!");
+  }
+
+  const chars = [...description];
+  if (chars.length <= maxChars) return description;
+  if (maxChars === 0) return "";
+  // The ellipsis takes the place of the last character kept
+  return `${chars.slice(0, maxChars - 1).join("").trimEnd()}…`;
+}
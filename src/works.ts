@@ -0,0 +1,247 @@
+import { type LinkedEntity, getLinkedEntities } from "./authors.ts";
+import type { SparqlSource } from "./client.ts";
+import { type PartialDate, dateClause, getDate } from "./dates.ts";
+import { PGCiteError } from "./errors.ts";
+import { type QID, parseQID, parseUrl, qidFromUrl } from "./ids.ts";
+import { type Row, escapeSPARQL, getOptionalValues, getValues, parseLanguage } from "./sparql.ts";
+
+// Optional information about a Work, which Wikidata may not have
+export interface WorkDetails {
+  publicationDate?: PartialDate;
+  publisher?: LinkedEntity;
+  publicationPlace?: LinkedEntity;
+}
+
+export class Work {
+  readonly title: string; // Ex. The Hitchhiker's Guide to the Galaxy
+  readonly id: QID; // Ex. Q25169
+  readonly idUrl: URL; // Ex. https://www.wikidata.org/entity/Q25169
+  readonly publicationDate?: PartialDate; // Ex. { year: 1979, month: 10, day: 12, precision: "day" }
+  readonly publisher?: LinkedEntity; // Publisher (P123), ex. { id: "Q7904587", label: "Pan Books" }
+  readonly publicationPlace?: LinkedEntity; // Place of publication (P291), ex. { id: "Q84", label: "London" }
+
+  constructor(title: string, id: string, details: WorkDetails = {}) {
+    this.title = title;
+    this.idUrl = parseUrl(id);
+    this.id = qidFromUrl(this.idUrl);
+    this.publicationDate = details.publicationDate;
+    this.publisher = details.publisher;
+    this.publicationPlace = details.publicationPlace;
+  }
+
+  toString() {
+    return `${this.id}: ${this.title}`;
+  }
+
+  toJSON() {
+    return {
+      title: this.title,
+      id: this.id,
+      idUrl: this.idUrl.href,
+      publicationDate: this.publicationDate,
+      publisher: this.publisher,
+      publicationPlace: this.publicationPlace,
+    };
+  }
+}
+
+// SPARQL for a work's publisher (P123) and place of publication (P291), for the label service to label
+// Only entities, not unknown values
+const publicationClause = `OPTIONAL {
+    ?work wdt:P123 ?publisher.
+    FILTER(STRSTARTS(STR(?publisher), STR(wd:)))
+  }
+  OPTIONAL {
+    ?work wdt:P291 ?place.
+    FILTER(STRSTARTS(STR(?place), STR(wd:)))
+  }`;
+
+// Get an entity and its label bound by publicationClause, if there is one
+function getLinked(bindings: Row, name: string): LinkedEntity | undefined {
+  const [entity, label] = getOptionalValues(bindings, name, `${name}Label`);
+  if (entity === undefined) return undefined;
+  const id = qidFromUrl(parseUrl(entity));
+  return { id, label: label ?? id };
+}
+
+// Get a work from a row that selects ?work, its title, ?published, ?publisher, and ?place
+function workFromBindings(bindings: Row, titleVariable = "workLabel"): Work {
+  const [id, title] = getValues(bindings, "work", titleVariable);
+  return new Work(title, id, {
+    publicationDate: getDate(bindings, "published"),
+    publisher: getLinked(bindings, "publisher"),
+    publicationPlace: getLinked(bindings, "place"),
+  });
+}
+
+// Get the works from the rows of a query like workFromBindings takes
+// Keyed by ID, to keep only the first of any duplicates
+// A work comes back once per combination of its dates, publishers, and places, if it has several
+function worksFromResults(results: Row[], titleVariable = "workLabel"): Work[] {
+  const works = new Map<QID, Work>();
+  for (const bindings of results) {
+    const work = workFromBindings(bindings, titleVariable);
+    if (!works.has(work.id)) works.set(work.id, work);
+  }
+
+  return [...works.values()];
+}
+
+// The SELECT clause of a query for workFromBindings, with the title in titleVariable
+function workProjection(titleVariable = "workLabel"): string {
+  return `SELECT
+  ?work               # Ex. Q25169
+  ${`?${titleVariable}`.padEnd(19)} # Ex. The Hitchhiker's Guide to the Galaxy
+  ?published          # Ex. 1979-10-12T00:00:00Z
+  ?publishedPrecision # Ex. 11 (day)
+  ?publisher          # Ex. Q7904587
+  ?publisherLabel     # Ex. Pan Books
+  ?place              # Ex. Q84
+  ?placeLabel         # Ex. London`;
+}
+
+// Get the works written (P50) or created (P170) by a given author, using an exact ID (ex. Q42)
+// Titles are labels in the given language, and works listed under both properties are only included once
+export async function getWorks(
+  client: SparqlSource,
+  author: QID,
+  lang = parseLanguage("en"),
+): Promise<Work[]> {
+  const query = `
+${workProjection()}
+WHERE {
+  { ?work wdt:P50 wd:${parseQID(author)}. } UNION { ?work wdt:P170 wd:${parseQID(author)}. }
+
+  ${dateClause("?work", "P577", "?published")}
+  ${publicationClause}
+
+  SERVICE wikibase:label { bd:serviceParam wikibase:language "${parseLanguage(lang)}". }
+}`;
+
+  return worksFromResults(await client.makeRequest(query));
+}
+
+// Get the notable works (P800) of a given author, using an exact ID (ex. Q42)
+// A shorter list than getWorks, for a summary; authors with no notable works give an empty list
+export async function getNotableWorks(
+  client: SparqlSource,
+  author: QID,
+  lang = parseLanguage("en"),
+): Promise<Work[]> {
+  const query = `
+${workProjection()}
+WHERE {
+  wd:${parseQID(author)} wdt:P800 ?work.
+
+  ${dateClause("?work", "P577", "?published")}
+  ${publicationClause}
+
+  SERVICE wikibase:label { bd:serviceParam wikibase:language "${parseLanguage(lang)}". }
+}`;
+
+  return worksFromResults(await client.makeRequest(query));
+}
+
+// Classes that a work found by title must be an instance of
+const workClasses: QID[] = [
+  "Q571", // book
+  "Q7725634", // literary work
+  "Q47461344", // written work
+  "Q8261", // novel
+];
+
+// Get a list of books and other written works with an exact title (e.g. "The Hitchhiker's Guide to the Galaxy")
+export async function getWorksByTitle(
+  client: SparqlSource,
+  title: string,
+  lang = parseLanguage("en"),
+): Promise<Work[]> {
+  parseLanguage(lang);
+
+  const query = `
+${workProjection("title")}
+WHERE {
+  VALUES ?title {
+    """${escapeSPARQL(title)}"""@${lang}
+  }
+  VALUES ?class {
+    ${workClasses.map((id) => `wd:${id}`).join(" ")}
+  }
+
+  ?work wdt:P31 ?class; # The ID of an instance of a kind of written work,
+    rdfs:label ?title.  # ...whose entity label matches ?title
+
+  ${dateClause("?work", "P577", "?published")}
+  ${publicationClause}
+
+  SERVICE wikibase:label { bd:serviceParam wikibase:language "${lang}". }
+}`;
+
+  // A work in several of the classes comes back once for each of them
+  return worksFromResults(await client.makeRequest(query), "title");
+}
+
+// Get the work with a given ISBN-13 (P212) or ISBN-10 (P957), if there is one
+// Hyphens and spaces are ignored, since Wikidata's copies of ISBNs are hyphenated differently from book to book
+// The ISBN is looked up as given and without hyphens first, and only if neither is on Wikidata
+// are every work's ISBNs compared with their hyphens removed, which is much slower
+// Throws a PGCiteError if the input isn't 10 or 13 digits (the last digit of an ISBN-10 may be X)
+export async function getWorkByIsbn(
+  client: SparqlSource,
+  isbn: string,
+  lang = parseLanguage("en"),
+): Promise<Work | undefined> {
+  const normalized = isbn.replace(/[-\s]/g, "").toUpperCase(); // Ex. 978-0-330-25864-7 -> 9780330258647
+  const property = /^\d{13}$/.test(normalized)
+    ? "P212"
+    : /^\d{9}[\dX]$/.test(normalized)
+      ? "P957"
+      : undefined;
+  if (property === undefined) {
+    throw new PGCiteError("invalidId", `'${isbn}' is not an ISBN-10 or ISBN-13!`);
+  }
+
+  const isbnQuery = (match: string) => `
+${workProjection()}
+WHERE {
+  ${match}
+
+  ${dateClause("?work", "P577", "?published")}
+  ${publicationClause}
+
+  SERVICE wikibase:label { bd:serviceParam wikibase:language "${parseLanguage(lang)}". }
+}
+LIMIT 1`;
+
+  const forms = [...new Set([isbn.trim().toUpperCase(), normalized])];
+  const exact = `VALUES ?isbn { ${forms.map((form) => `"${escapeSPARQL(form)}"`).join(" ")} }
+  ?work wdt:${property} ?isbn.`;
+  const [bindings] = await client.makeRequest(isbnQuery(exact));
+  if (bindings !== undefined) return workFromBindings(bindings);
+
+  const anyHyphenation = `?work wdt:${property} ?isbn.
+  FILTER(UCASE(REPLACE(?isbn, "[- ]", "")) = "${escapeSPARQL(normalized)}")`;
+  const [fallback] = await client.makeRequest(isbnQuery(anyHyphenation));
+  return fallback === undefined ? undefined : workFromBindings(fallback);
+}
+
+// What getWorkGenres reads, ex. P136 (genre) for "science fiction", or P921 (main subject) for "time travel"
+export type GenreProperty = "P136" | "P921";
+
+// Get the genres (P136) or main subjects (P921) of a work, using an exact ID (ex. Q25169), ex. for filtering
+// With both properties, ones that are a genre and a subject are only included once, in their first place
+export async function getWorkGenres(
+  client: SparqlSource,
+  work: QID,
+  properties: GenreProperty[] = ["P136"],
+  lang = parseLanguage("en"),
+): Promise<LinkedEntity[]> {
+  const genres = new Map<QID, LinkedEntity>(); // Keyed by ID, to keep only the first of any duplicates
+  for (const property of properties) {
+    for (const genre of await getLinkedEntities(client, work, property, lang)) {
+      if (!genres.has(genre.id)) genres.set(genre.id, genre);
+    }
+  }
+
+  return [...genres.values()];
+}